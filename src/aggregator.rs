@@ -0,0 +1,107 @@
+//! Split-order (smurfing) aggregation
+//!
+//! Insiders break a large position into many small fills to stay under the
+//! size filter. This keeps a rolling sum of taker BUY fills per
+//! (wallet, market, outcome) so the combined position can be evaluated.
+
+use std::collections::HashMap;
+
+use crate::types::Trade;
+
+/// (wallet, condition_id, outcome)
+type PositionKey = (String, String, String);
+
+/// Rolling per-position accumulator of small taker BUY fills
+pub struct SplitOrderAggregator {
+    positions: HashMap<PositionKey, Vec<Trade>>,
+}
+
+impl SplitOrderAggregator {
+    pub fn new() -> Self {
+        Self {
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Record a fill. Returns the constituent fills (oldest first) once the
+    /// rolling total inside `window_secs` reaches `threshold_usd`.
+    ///
+    /// The position is reset after it fires, so a wallet has to accumulate
+    /// another full threshold before it alerts again.
+    pub fn record(&mut self, trade: &Trade, window_secs: i64, threshold_usd: f64) -> Option<Vec<Trade>> {
+        let key = position_key(trade)?;
+        let fills = self.positions.entry(key.clone()).or_default();
+
+        // Drop fills that fell out of the window relative to this one
+        fills.retain(|f| trade.timestamp - f.timestamp <= window_secs);
+        fills.push(trade.clone());
+
+        let total: f64 = fills.iter().map(|f| f.value_usd()).sum();
+        if fills.len() > 1 && total >= threshold_usd {
+            return self.positions.remove(&key);
+        }
+
+        None
+    }
+
+    /// Forget positions whose newest fill is older than the window
+    pub fn prune(&mut self, now_ts: i64, window_secs: i64) {
+        self.positions.retain(|_, fills| {
+            fills
+                .last()
+                .map(|f| now_ts - f.timestamp <= window_secs)
+                .unwrap_or(false)
+        });
+    }
+}
+
+fn position_key(trade: &Trade) -> Option<PositionKey> {
+    let market = trade.market_key()?;
+    Some((trade.proxy_wallet.to_lowercase(), market, trade.outcome_key()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::trade;
+
+    const WINDOW: i64 = 900;
+    const THRESHOLD: f64 = 5_000.0;
+
+    /// A $2,000 taker BUY by `wallet` at `timestamp`
+    fn fill(wallet: &str, timestamp: i64) -> Trade {
+        trade(wallet, "BUY", 0.2, 10_000.0, timestamp)
+    }
+
+    #[test]
+    fn fires_once_when_fills_cross_the_threshold() {
+        let mut aggregator = SplitOrderAggregator::new();
+        assert!(aggregator.record(&fill("0xa", 0), WINDOW, THRESHOLD).is_none());
+        assert!(aggregator.record(&fill("0xa", 60), WINDOW, THRESHOLD).is_none());
+
+        let fills = aggregator.record(&fill("0xa", 120), WINDOW, THRESHOLD).unwrap();
+        assert_eq!(fills.iter().map(|f| f.timestamp).collect::<Vec<_>>(), vec![0, 60, 120]);
+
+        // Reset after firing: the next fill starts a new position
+        assert!(aggregator.record(&fill("0xa", 180), WINDOW, THRESHOLD).is_none());
+        assert!(aggregator.record(&fill("0xa", 240), WINDOW, THRESHOLD).is_none());
+    }
+
+    #[test]
+    fn fills_outside_the_window_do_not_count() {
+        let mut aggregator = SplitOrderAggregator::new();
+        assert!(aggregator.record(&fill("0xa", 0), WINDOW, THRESHOLD).is_none());
+        assert!(aggregator.record(&fill("0xa", 600), WINDOW, THRESHOLD).is_none());
+        // The first fill is 901s old by now
+        assert!(aggregator.record(&fill("0xa", WINDOW + 1), WINDOW, THRESHOLD).is_none());
+        assert!(aggregator.record(&fill("0xa", WINDOW + 60), WINDOW, THRESHOLD).is_some());
+    }
+
+    #[test]
+    fn wallets_are_aggregated_separately() {
+        let mut aggregator = SplitOrderAggregator::new();
+        for (i, wallet) in ["0xa", "0xb", "0xc"].into_iter().enumerate() {
+            assert!(aggregator.record(&fill(wallet, i as i64), WINDOW, THRESHOLD).is_none());
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Alert history: every alert is persisted as one JSON line in DATA_DIR
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{append_jsonl, load_jsonl};
+use crate::types::{AlertLevel, Detector, SuspectTrade};
+
+const ALERTS_FILE: &str = "alerts.jsonl";
+
+/// Snapshot of a `SuspectTrade` at the time it was alerted
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlertRecord {
+    pub id: String,
+    pub created_at: i64,
+    pub detector: Detector,
+    pub alert_level: AlertLevel,
+    pub score: f64,
+    pub reason: String,
+    /// Wallet the alert is about
+    pub wallet: String,
+    /// Every wallet involved (cluster members included)
+    pub wallets: Vec<String>,
+    pub condition_id: Option<String>,
+    pub asset: Option<String>,
+    pub market_title: Option<String>,
+    pub event_slug: Option<String>,
+    pub outcome: Option<String>,
+    pub outcome_index: Option<i32>,
+    pub side: String,
+    pub price: f64,
+    pub value_usd: f64,
+    pub bet: Option<String>,
+    pub market_url: String,
+    pub transaction_hash: Option<String>,
+    pub trade_timestamp: i64,
+}
+
+impl AlertRecord {
+    pub fn from_suspect(suspect: &SuspectTrade) -> Self {
+        let trade = &suspect.trade;
+        let mut wallets = vec![trade.proxy_wallet.to_lowercase()];
+        for fill in &suspect.fills {
+            let wallet = fill.proxy_wallet.to_lowercase();
+            if !wallets.contains(&wallet) {
+                wallets.push(wallet);
+            }
+        }
+
+        Self {
+            id: suspect.alert_id(),
+            created_at: chrono::Utc::now().timestamp(),
+            detector: suspect.detector,
+            alert_level: suspect.alert_level,
+            score: suspect.score.total(),
+            reason: suspect.reason.clone(),
+            wallet: trade.proxy_wallet.to_lowercase(),
+            wallets,
+            condition_id: trade.condition_id.clone(),
+            asset: trade.asset.clone(),
+            market_title: trade.title.clone(),
+            event_slug: trade.event_slug.clone(),
+            outcome: trade.outcome.clone(),
+            outcome_index: trade.outcome_index,
+            side: trade.side.clone(),
+            price: trade.price,
+            value_usd: suspect.position_value_usd(),
+            bet: suspect.bet.clone(),
+            market_url: trade.market_url(),
+            transaction_hash: trade.transaction_hash.clone(),
+            trade_timestamp: trade.timestamp,
+        }
+    }
+}
+
+/// Persist an alert
+pub fn record_alert(suspect: &SuspectTrade) -> Result<()> {
+    append_jsonl(ALERTS_FILE, &AlertRecord::from_suspect(suspect))
+}
+
+/// Every persisted alert, oldest first
+pub fn load_alerts() -> Result<Vec<AlertRecord>> {
+    load_jsonl(ALERTS_FILE)
+}
+
+/// Persisted alerts involving a wallet
+pub fn alerts_for_wallet(wallet: &str) -> Result<Vec<AlertRecord>> {
+    let wallet = wallet.to_lowercase();
+    Ok(load_alerts()?
+        .into_iter()
+        .filter(|a| a.wallets.contains(&wallet))
+        .collect())
+}
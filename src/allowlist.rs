@@ -0,0 +1,156 @@
+//! Allowlist of known market makers and funds
+//!
+//! Market-maker and fund proxy wallets are sometimes newly deployed, so they
+//! look fresh and trip the detectors. Wallets and pseudonyms listed in
+//! ALLOWLIST_FILE are never alerted on (or capped at LOW), and wallets that
+//! behave like market makers (mostly maker fills, quoting both sides) are
+//! suppressed automatically.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::allowlist_file;
+use crate::types::Trade;
+
+/// What to do with an allowlisted wallet's alerts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowAction {
+    /// Never alert
+    #[default]
+    Suppress,
+    /// Alert, but never above LOW
+    CapLow,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AllowEntry {
+    pub name: String,
+    #[serde(default)]
+    pub action: AllowAction,
+}
+
+/// `{ "wallets": { "0x...": { "name": "...", "action": "suppress" } }, "pseudonyms": { ... } }`
+#[derive(Debug, Default, Deserialize)]
+pub struct Allowlist {
+    #[serde(default)]
+    wallets: HashMap<String, AllowEntry>,
+    #[serde(default)]
+    pseudonyms: HashMap<String, AllowEntry>,
+}
+
+impl Allowlist {
+    /// Load from ALLOWLIST_FILE. Missing or invalid file = empty allowlist.
+    pub fn load() -> Self {
+        let Some(path) = allowlist_file() else {
+            return Self::default();
+        };
+        match Self::read(&path) {
+            Ok(allowlist) => allowlist,
+            Err(e) => {
+                eprintln!("⚠️  Allowlist not loaded: {:#}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn read(path: &str) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let allowlist: Allowlist =
+            serde_json::from_str(&raw).with_context(|| format!("Invalid allowlist JSON in {}", path))?;
+        Ok(Self {
+            wallets: lowercase_keys(allowlist.wallets),
+            pseudonyms: lowercase_keys(allowlist.pseudonyms),
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.wallets.len() + self.pseudonyms.len()
+    }
+
+    /// Entry matching a trade's wallet, or failing that its pseudonym
+    pub fn lookup(&self, trade: &Trade) -> Option<&AllowEntry> {
+        self.wallets.get(&trade.proxy_wallet.to_lowercase()).or_else(|| {
+            trade
+                .pseudonym
+                .as_ref()
+                .and_then(|p| self.pseudonyms.get(&p.to_lowercase()))
+        })
+    }
+}
+
+fn lowercase_keys(map: HashMap<String, AllowEntry>) -> HashMap<String, AllowEntry> {
+    map.into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect()
+}
+
+// ============================================================================
+// MARKET-MAKER DETECTION
+// ============================================================================
+
+/// Thresholds for calling a wallet a market maker
+pub struct MarketMakerParams {
+    pub min_fills: usize,
+    pub min_maker_ratio: f64,
+    pub min_two_sided_ratio: f64,
+}
+
+/// How a wallet's recent fills split between maker and taker, and sides
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketMakerStats {
+    pub fills: usize,
+    /// Share of fills where the wallet's resting order was hit
+    pub maker_ratio: f64,
+    /// Share of traded assets the wallet both bought and sold
+    pub two_sided_ratio: f64,
+}
+
+impl MarketMakerStats {
+    /// Compare all fills against taker-only fills over the same period
+    pub fn from_fills(all_fills: &[Trade], taker_fills: &[Trade]) -> Self {
+        let since = all_fills.iter().map(|t| t.timestamp).min().unwrap_or(0);
+        let taker_count = taker_fills.iter().filter(|t| t.timestamp >= since).count();
+        let maker_ratio = if all_fills.is_empty() {
+            0.0
+        } else {
+            1.0 - (taker_count.min(all_fills.len()) as f64 / all_fills.len() as f64)
+        };
+
+        let mut sides: HashMap<String, HashSet<bool>> = HashMap::new();
+        for fill in all_fills {
+            let asset = fill.asset.clone().unwrap_or_else(|| {
+                format!("{}:{}", fill.market_key().unwrap_or_default(), fill.outcome_key())
+            });
+            sides.entry(asset).or_default().insert(fill.is_taker_buy());
+        }
+        let two_sided = sides.values().filter(|s| s.len() == 2).count();
+        let two_sided_ratio = if sides.is_empty() {
+            0.0
+        } else {
+            two_sided as f64 / sides.len() as f64
+        };
+
+        Self {
+            fills: all_fills.len(),
+            maker_ratio,
+            two_sided_ratio,
+        }
+    }
+
+    pub fn is_market_maker(&self, params: &MarketMakerParams) -> bool {
+        self.fills >= params.min_fills
+            && self.maker_ratio >= params.min_maker_ratio
+            && self.two_sided_ratio >= params.min_two_sided_ratio
+    }
+
+    /// e.g. "312 fills, 91% maker, 78% two-sided"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} fills, {:.0}% maker, {:.0}% two-sided",
+            self.fills,
+            self.maker_ratio * 100.0,
+            self.two_sided_ratio * 100.0
+        )
+    }
+}
@@ -0,0 +1,326 @@
+//! Market-level volume and price anomaly detection
+//!
+//! Odds moving from 3.6% to 70% before an announcement can be driven by many
+//! established wallets and never trip the fresh-wallet filter. This keeps
+//! rolling per-asset baselines from the observed trade stream and flags
+//! taker-buy volume spikes and sustained price drift.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::config::{
+    anomaly_baseline_buckets, anomaly_bucket_secs, anomaly_min_volume_usd, anomaly_volume_zscore,
+    price_drift_min_trades, price_drift_threshold, price_drift_window_secs,
+};
+use crate::types::Trade;
+
+/// Smallest baseline deviation, as a fraction of the baseline mean
+const MIN_RELATIVE_STD_DEV: f64 = 0.25;
+
+/// Thresholds for market anomalies
+pub struct AnomalyParams {
+    /// Width of a volume bucket (seconds)
+    pub bucket_secs: i64,
+    /// Number of past buckets forming the volume baseline
+    pub baseline_buckets: usize,
+    /// Standard deviations above the baseline mean to count as a spike
+    pub volume_zscore: f64,
+    /// Minimum taker-buy volume in a bucket before it can be a spike
+    pub min_volume_usd: f64,
+    /// Absolute price move (0-1) that counts as drift
+    pub drift_threshold: f64,
+    /// Window over which drift is measured (seconds)
+    pub drift_window_secs: i64,
+    /// Trades at each end of the window that must agree on the move
+    pub drift_min_trades: usize,
+}
+
+impl AnomalyParams {
+    pub fn from_config() -> Self {
+        Self {
+            bucket_secs: anomaly_bucket_secs(),
+            baseline_buckets: anomaly_baseline_buckets(),
+            volume_zscore: anomaly_volume_zscore(),
+            min_volume_usd: anomaly_min_volume_usd(),
+            drift_threshold: price_drift_threshold(),
+            drift_window_secs: price_drift_window_secs(),
+            drift_min_trades: price_drift_min_trades(),
+        }
+    }
+}
+
+/// A detected market anomaly
+pub enum Anomaly {
+    VolumeSpike {
+        volume_usd: f64,
+        baseline_usd: f64,
+        zscore: f64,
+        trades: Vec<Trade>,
+    },
+    PriceDrift {
+        from_price: f64,
+        to_price: f64,
+        span_secs: i64,
+        trades: Vec<Trade>,
+    },
+}
+
+struct VolumeBucket {
+    start: i64,
+    volume_usd: f64,
+    trades: Vec<Trade>,
+}
+
+impl VolumeBucket {
+    fn empty(start: i64) -> Self {
+        Self {
+            start,
+            volume_usd: 0.0,
+            trades: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AssetHistory {
+    buckets: VecDeque<VolumeBucket>,
+    prices: VecDeque<Trade>,
+    last_spike_bucket: Option<i64>,
+    last_drift_alert: Option<i64>,
+    last_seen: i64,
+}
+
+/// Rolling per-asset volume and price baselines
+pub struct MarketAnomalyDetector {
+    assets: HashMap<String, AssetHistory>,
+}
+
+impl MarketAnomalyDetector {
+    pub fn new() -> Self {
+        Self {
+            assets: HashMap::new(),
+        }
+    }
+
+    /// Feed a trade (BUY or SELL) and return any anomaly it completes
+    pub fn record(&mut self, trade: &Trade, params: &AnomalyParams) -> Vec<Anomaly> {
+        let Some(asset) = trade.asset.clone().or_else(|| trade.market_key()) else {
+            return Vec::new();
+        };
+        let history = self.assets.entry(asset).or_default();
+        history.last_seen = history.last_seen.max(trade.timestamp);
+
+        let mut anomalies = Vec::new();
+        if trade.is_taker_buy() {
+            if let Some(spike) = history.record_volume(trade, params) {
+                anomalies.push(spike);
+            }
+        }
+        if let Some(drift) = history.record_price(trade, params) {
+            anomalies.push(drift);
+        }
+        anomalies
+    }
+
+    /// Forget assets that have been quiet for longer than the baseline span
+    pub fn prune(&mut self, now_ts: i64, params: &AnomalyParams) {
+        let span = params.bucket_secs * params.baseline_buckets as i64;
+        self.assets.retain(|_, h| now_ts - h.last_seen <= span.max(params.drift_window_secs));
+    }
+}
+
+impl AssetHistory {
+    fn record_volume(&mut self, trade: &Trade, params: &AnomalyParams) -> Option<Anomaly> {
+        let bucket_start = trade.timestamp - trade.timestamp.rem_euclid(params.bucket_secs);
+
+        match self.buckets.back().map(|b| b.start) {
+            Some(last) if bucket_start < last => {
+                // Late trade: credit its bucket if we still have it, never alert on it
+                if let Some(b) = self.buckets.iter_mut().find(|b| b.start == bucket_start) {
+                    b.volume_usd += trade.value_usd();
+                    b.trades.push(trade.clone());
+                }
+                return None;
+            }
+            Some(last) if bucket_start > last => {
+                // Fill quiet buckets with zero volume so the baseline reflects them
+                let gap = ((bucket_start - last) / params.bucket_secs - 1) as usize;
+                let fill = gap.min(params.baseline_buckets);
+                for i in (1..=fill).rev() {
+                    let start = bucket_start - i as i64 * params.bucket_secs;
+                    self.buckets.push_back(VolumeBucket::empty(start));
+                }
+                self.buckets.push_back(VolumeBucket::empty(bucket_start));
+            }
+            Some(_) => {}
+            None => self.buckets.push_back(VolumeBucket::empty(bucket_start)),
+        }
+
+        while self.buckets.len() > params.baseline_buckets + 1 {
+            self.buckets.pop_front();
+        }
+
+        let current = self.buckets.back_mut()?;
+        current.volume_usd += trade.value_usd();
+        current.trades.push(trade.clone());
+        let volume_usd = current.volume_usd;
+
+        // Need a meaningful baseline before judging spikes
+        let baseline: Vec<f64> = self
+            .buckets
+            .iter()
+            .take(self.buckets.len() - 1)
+            .map(|b| b.volume_usd)
+            .collect();
+        if baseline.len() < params.baseline_buckets / 2 || baseline.is_empty() {
+            return None;
+        }
+        if volume_usd < params.min_volume_usd || self.last_spike_bucket == Some(bucket_start) {
+            return None;
+        }
+
+        let mean = baseline.iter().sum::<f64>() / baseline.len() as f64;
+        let variance = baseline.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / baseline.len() as f64;
+        // Floor the deviation relative to the mean, so a steady baseline
+        // doesn't turn a few dollars over average into a huge z-score
+        let std_dev = variance.sqrt().max(mean * MIN_RELATIVE_STD_DEV).max(1.0);
+        let zscore = (volume_usd - mean) / std_dev;
+
+        if zscore < params.volume_zscore {
+            return None;
+        }
+
+        self.last_spike_bucket = Some(bucket_start);
+        Some(Anomaly::VolumeSpike {
+            volume_usd,
+            baseline_usd: mean,
+            zscore,
+            trades: self.buckets.back()?.trades.clone(),
+        })
+    }
+
+    fn record_price(&mut self, trade: &Trade, params: &AnomalyParams) -> Option<Anomaly> {
+        self.prices.push_back(trade.clone());
+        self.prices
+            .retain(|t| trade.timestamp - t.timestamp <= params.drift_window_secs);
+
+        let k = params.drift_min_trades.max(1);
+        if self.prices.len() < k * 2 {
+            return None;
+        }
+        if let Some(last) = self.last_drift_alert {
+            if trade.timestamp - last < params.drift_window_secs {
+                return None;
+            }
+        }
+
+        let first: Vec<f64> = self.prices.iter().take(k).map(|t| t.price).collect();
+        let recent: Vec<f64> = self.prices.iter().rev().take(k).map(|t| t.price).collect();
+        let from_price = first.iter().sum::<f64>() / k as f64;
+        let to_price = recent.iter().sum::<f64>() / k as f64;
+
+        // Sustained: every recent trade is past the threshold, not just one print
+        let direction = (to_price - from_price).signum();
+        let sustained = recent.iter().all(|p| {
+            (p - from_price).abs() >= params.drift_threshold && (p - from_price).signum() == direction
+        });
+        if !sustained {
+            return None;
+        }
+
+        let span_secs = trade.timestamp - self.prices.front()?.timestamp;
+        self.last_drift_alert = Some(trade.timestamp);
+        Some(Anomaly::PriceDrift {
+            from_price,
+            to_price,
+            span_secs,
+            trades: self.prices.iter().cloned().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::trade;
+
+    const BUCKET: i64 = 60;
+
+    fn params() -> AnomalyParams {
+        AnomalyParams {
+            bucket_secs: BUCKET,
+            baseline_buckets: 10,
+            volume_zscore: 3.0,
+            min_volume_usd: 1_000.0,
+            // Out of reach, so only volume is under test
+            drift_threshold: 1.0,
+            drift_window_secs: 3600,
+            drift_min_trades: 3,
+        }
+    }
+
+    /// A taker BUY worth `value_usd` in bucket `bucket`
+    fn buy(value_usd: f64, bucket: i64) -> Trade {
+        trade("0xa", "BUY", 0.5, value_usd * 2.0, bucket * BUCKET + 1)
+    }
+
+    fn spikes(detector: &mut MarketAnomalyDetector, trade: &Trade) -> usize {
+        detector
+            .record(trade, &params())
+            .iter()
+            .filter(|a| matches!(a, Anomaly::VolumeSpike { .. }))
+            .count()
+    }
+
+    #[test]
+    fn no_spike_before_the_baseline_warms_up() {
+        let mut detector = MarketAnomalyDetector::new();
+        for bucket in 0..3 {
+            assert_eq!(spikes(&mut detector, &buy(2_000.0, bucket)), 0);
+        }
+        // Half the baseline buckets are needed
+        assert_eq!(spikes(&mut detector, &buy(50_000.0, 3)), 0);
+    }
+
+    #[test]
+    fn spike_over_baseline_fires_once_per_bucket() {
+        let mut detector = MarketAnomalyDetector::new();
+        for bucket in 0..10 {
+            assert_eq!(spikes(&mut detector, &buy(2_000.0, bucket)), 0);
+        }
+        // Within the relative deviation floor: not a spike
+        assert_eq!(spikes(&mut detector, &buy(2_400.0, 10)), 0);
+
+        let mut detector = MarketAnomalyDetector::new();
+        for bucket in 0..10 {
+            spikes(&mut detector, &buy(2_000.0, bucket));
+        }
+        let anomalies = detector.record(&buy(20_000.0, 10), &params());
+        match anomalies.as_slice() {
+            [Anomaly::VolumeSpike {
+                volume_usd,
+                baseline_usd,
+                trades,
+                ..
+            }] => {
+                assert_eq!(*volume_usd, 20_000.0);
+                assert_eq!(*baseline_usd, 2_000.0);
+                assert_eq!(trades.len(), 1);
+            }
+            _ => panic!("expected a single volume spike"),
+        }
+
+        // More volume in the same bucket doesn't alert again
+        assert_eq!(spikes(&mut detector, &buy(20_000.0, 10)), 0);
+    }
+
+    #[test]
+    fn quiet_buckets_count_as_zero_volume() {
+        let mut detector = MarketAnomalyDetector::new();
+        for bucket in 0..10 {
+            spikes(&mut detector, &buy(2_000.0, bucket));
+        }
+        // After a long silence the baseline is all zeros, so modest volume
+        // is a spike against it
+        assert_eq!(spikes(&mut detector, &buy(1_500.0, 30)), 1);
+    }
+}
@@ -6,8 +6,8 @@ use std::collections::HashSet;
 use std::time::Duration;
 use tokio::time::sleep;
 
-use crate::config::{DATA_API_BASE, INITIAL_BACKOFF_MS, MAX_BACKOFF_MS, BACKOFF_MULTIPLIER};
-use crate::types::{Trade, UserActivity, UserStats};
+use crate::config::{CLOB_API_BASE, DATA_API_BASE, GAMMA_API_BASE, INITIAL_BACKOFF_MS, MAX_BACKOFF_MS, BACKOFF_MULTIPLIER};
+use crate::types::{Event, Market, Position, PricePoint, PriceHistory, Trade, UserActivity, UserStats};
 
 /// HTTP client with retry logic
 pub struct ApiClient {
@@ -84,6 +84,59 @@ impl ApiClient {
         }
     }
 
+    /// Fetch a wallet's own fills; with `taker_only = false` its maker fills are included
+    pub async fn fetch_user_trades(&mut self, address: &str, taker_only: bool) -> Result<Vec<Trade>> {
+        let url = format!(
+            "{}/trades?user={}&takerOnly={}&limit=500",
+            DATA_API_BASE, address, taker_only
+        );
+        self.request_with_retry(&url, &format!("trades({}...)", &address[..8.min(address.len())])).await
+    }
+
+    /// Fetch a wallet's current open positions
+    pub async fn fetch_positions(&mut self, address: &str) -> Result<Vec<Position>> {
+        let url = format!("{}/positions?user={}&sizeThreshold=1&limit=500", DATA_API_BASE, address);
+        self.request_with_retry(&url, &format!("positions({}...)", &address[..8.min(address.len())])).await
+    }
+
+    /// Fetch market metadata (end date, status) from the Gamma API
+    pub async fn fetch_market(&mut self, condition_id: &str) -> Result<Option<Market>> {
+        let url = format!("{}/markets?condition_ids={}", GAMMA_API_BASE, condition_id);
+        let context = format!("market({}...)", &condition_id[..10.min(condition_id.len())]);
+        let markets: Vec<Market> = self.request_with_retry(&url, &context).await?;
+        Ok(markets.into_iter().next())
+    }
+
+    /// Fetch several markets in one request (e.g. to check resolutions)
+    pub async fn fetch_markets(&mut self, condition_ids: &[String]) -> Result<Vec<Market>> {
+        if condition_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let query: Vec<String> = condition_ids
+            .iter()
+            .map(|id| format!("condition_ids={}", id))
+            .collect();
+        let url = format!("{}/markets?{}&limit={}", GAMMA_API_BASE, query.join("&"), condition_ids.len());
+        self.request_with_retry(&url, &format!("markets({})", condition_ids.len())).await
+    }
+
+    /// Fetch an event with all its sibling markets from the Gamma API
+    pub async fn fetch_event(&mut self, slug: &str) -> Result<Option<Event>> {
+        let url = format!("{}/events?slug={}", GAMMA_API_BASE, slug);
+        let events: Vec<Event> = self.request_with_retry(&url, &format!("event({})", slug)).await?;
+        Ok(events.into_iter().next())
+    }
+
+    /// Fetch an outcome token's price history between two timestamps (hourly points)
+    pub async fn fetch_price_history(&mut self, asset: &str, start_ts: i64, end_ts: i64) -> Result<Vec<PricePoint>> {
+        let url = format!(
+            "{}/prices-history?market={}&startTs={}&endTs={}&fidelity=60",
+            CLOB_API_BASE, asset, start_ts, end_ts
+        );
+        let history: PriceHistory = self.request_with_retry(&url, "prices-history").await?;
+        Ok(history.history)
+    }
+
     /// Calculate user stats from their activity
     pub fn calculate_user_stats(address: &str, activities: &[UserActivity]) -> UserStats {
         let mut unique_markets: HashSet<String> = HashSet::new();
@@ -115,6 +168,8 @@ impl ApiClient {
             unique_markets: unique_markets.len(),
             total_trades,
             first_activity_timestamp: min_timestamp,
+            funding: None,
+            record: None,
         }
     }
 }
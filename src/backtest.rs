@@ -0,0 +1,493 @@
+//! `backtest` command: replay stored trades through the detectors
+//!
+//! Tuning MIN_TRADE_SIZE_USD, MAX_PRICE_THRESHOLD and MAX_UNIQUE_MARKETS is
+//! guesswork without history. This replays a recorded trade set (see
+//! RECORD_TRADES) in time order through the live detection pipeline
+//! (`detect`): allowlist, watched-market thresholds, every trade-stream
+//! detector, pre-resolution gating, scoring and repeat merging. Each alert
+//! is then scored against its market's resolution, and the report shows how
+//! following the alerts would have done.
+//!
+//! Wallet freshness is judged from the trade set itself, so the first
+//! `warmup_hours` only build history and produce no alerts. What needs live
+//! lookups is not replayed: market-maker detection, funding, track records
+//! (today's record would leak the future), linkage, negRisk sibling context
+//! and watched wallets.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Serialize;
+
+use crate::aggregator::SplitOrderAggregator;
+use crate::alerts::AlertRecord;
+use crate::allowlist::{AllowAction, Allowlist};
+use crate::anomaly::{AnomalyParams, MarketAnomalyDetector};
+use crate::api::ApiClient;
+use crate::cluster::{cluster_wallets, ClusterDetector, ClusterParams};
+use crate::config::{
+    cluster_min_trade_usd, cluster_min_value_usd, cluster_min_wallets, cluster_window_secs, exit_min_value_usd,
+    is_gambling_market, split_order_window_secs, Thresholds,
+};
+use crate::dedup::AlertDedup;
+use crate::detect;
+use crate::followup::{alert_outcome_index, position_result, AlertOutcome};
+use crate::market_watchlist::MarketWatchlist;
+use crate::outcomes::{bet_context, BetContext};
+use crate::report::{build_report, print_report, AlertStats, Report};
+use crate::storage::{data_path, load_json, save_json};
+use crate::types::{AlertLevel, Detector, Market, SuspectTrade, Trade, UserStats};
+
+const RESOLUTIONS_FILE: &str = "market_resolutions.json";
+
+/// Values to sweep; an empty list means "the configured value"
+#[derive(Debug, Default)]
+pub struct ParamGrid {
+    pub min_size: Vec<f64>,
+    pub max_price: Vec<f64>,
+    pub max_markets: Vec<usize>,
+    pub max_age_hours: Vec<u64>,
+}
+
+impl ParamGrid {
+    /// Every combination of the grid's values
+    fn configs(&self) -> Vec<Thresholds> {
+        let base = Thresholds::standard();
+        let or_base = |values: &[f64], default: f64| if values.is_empty() { vec![default] } else { values.to_vec() };
+        let min_sizes = or_base(&self.min_size, base.min_trade_size_usd);
+        let max_prices = or_base(&self.max_price, base.max_price);
+        let max_markets = if self.max_markets.is_empty() { vec![base.max_unique_markets] } else { self.max_markets.clone() };
+        let max_ages = if self.max_age_hours.is_empty() { vec![base.max_wallet_age_hours] } else { self.max_age_hours.clone() };
+
+        let mut configs = Vec::new();
+        for &min_trade_size_usd in &min_sizes {
+            for &max_price in &max_prices {
+                for &max_unique_markets in &max_markets {
+                    for &max_wallet_age_hours in &max_ages {
+                        configs.push(Thresholds {
+                            min_trade_size_usd,
+                            max_price,
+                            max_unique_markets,
+                            max_wallet_age_hours,
+                        });
+                    }
+                }
+            }
+        }
+        configs
+    }
+}
+
+pub struct BacktestArgs {
+    /// JSON-lines (or JSON array) of trades; defaults to `DATA_DIR/trades.jsonl`
+    pub trades_file: Option<String>,
+    pub grid: ParamGrid,
+    pub warmup_hours: i64,
+    /// Stake assumed when following each alert
+    pub stake_usd: f64,
+    /// Fetch missing resolutions from the Gamma API
+    pub fetch_resolutions: bool,
+    pub json: bool,
+}
+
+/// Result of replaying one config
+#[derive(Debug, Serialize)]
+pub struct BacktestRun {
+    pub thresholds: Thresholds,
+    /// PnL of staking `stake_usd` on every resolved alert
+    pub follow_pnl_usd: f64,
+    pub report: Report,
+}
+
+/// Run the `backtest` command
+pub async fn run_backtest_command(args: BacktestArgs) -> Result<()> {
+    let path = args
+        .trades_file
+        .clone()
+        .unwrap_or_else(|| data_path("trades.jsonl").display().to_string());
+    let trades = load_trades(&path)?;
+    let markets = load_resolutions(&trades, args.fetch_resolutions).await?;
+
+    let allowlist = Allowlist::load();
+    let watchlist = MarketWatchlist::load();
+
+    let resolved = markets.values().filter(|m| m.winning_outcome_index().is_some()).count();
+    if !args.json {
+        println!(
+            "{} Replaying {} trades from {} ({} of {} markets resolved)",
+            "⏪".cyan(),
+            trades.len(),
+            path,
+            resolved,
+            markets.len()
+        );
+    }
+
+    let mut runs: Vec<BacktestRun> = args
+        .grid
+        .configs()
+        .into_iter()
+        .map(|thresholds| {
+            let (alerts, outcomes) = replay(&trades, &markets, &thresholds, args.warmup_hours, &allowlist, &watchlist);
+            let report = build_report(&alerts, &outcomes);
+            let follow_pnl_usd = report.overall.avg_return.unwrap_or(0.0) * report.overall.resolved as f64 * args.stake_usd;
+            BacktestRun {
+                thresholds,
+                follow_pnl_usd,
+                report,
+            }
+        })
+        .collect();
+    runs.sort_by(|a, b| b.follow_pnl_usd.total_cmp(&a.follow_pnl_usd));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+    } else if let [run] = runs.as_slice() {
+        print_config(&run.thresholds, run.follow_pnl_usd, args.stake_usd);
+        print_report(&run.report);
+    } else {
+        print_comparison(&runs, args.stake_usd);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// REPLAY
+// ============================================================================
+
+/// What the replay knows about a wallet from earlier trades in the set
+#[derive(Default)]
+struct WalletHistory {
+    first_ts: Option<i64>,
+    markets: HashSet<String>,
+    trades: usize,
+}
+
+impl WalletHistory {
+    fn stats(&self, address: &str) -> UserStats {
+        UserStats {
+            address: address.to_string(),
+            unique_markets: self.markets.len(),
+            total_trades: self.trades,
+            first_activity_timestamp: self.first_ts,
+            funding: None,
+            record: None,
+        }
+    }
+}
+
+/// Replay `trades` (sorted by time) with one threshold set, returning the
+/// alerts it would have raised and their outcomes. Each trade goes through
+/// the same decisions as a live poll (see `detect`), with wallet stats taken
+/// from the trades before it.
+fn replay(
+    trades: &[Trade],
+    markets: &HashMap<String, Market>,
+    thresholds: &Thresholds,
+    warmup_hours: i64,
+    allowlist: &Allowlist,
+    watchlist: &MarketWatchlist,
+) -> (Vec<AlertRecord>, HashMap<String, AlertOutcome>) {
+    let mut history: HashMap<String, WalletHistory> = HashMap::new();
+    let mut split_orders = SplitOrderAggregator::new();
+    let mut clusters = ClusterDetector::new();
+    let mut anomalies = MarketAnomalyDetector::new();
+    let mut dedup = AlertDedup::default();
+    let mut flagged: HashMap<String, AlertLevel> = HashMap::new();
+    let split_window = split_order_window_secs();
+    let cluster_params = ClusterParams {
+        min_wallets: cluster_min_wallets(),
+        window_secs: cluster_window_secs(),
+        min_value_usd: cluster_min_value_usd(),
+    };
+    let cluster_min_trade = cluster_min_trade_usd();
+    let anomaly_params = AnomalyParams::from_config();
+    let exit_min = exit_min_value_usd();
+    let alerts_from = trades.first().map(|t| t.timestamp + warmup_hours * 3600).unwrap_or(0);
+
+    let stats_of = |history: &HashMap<String, WalletHistory>, address: &str| {
+        let wallet = address.to_lowercase();
+        history.get(&wallet).map(|h| h.stats(&wallet)).unwrap_or_else(|| WalletHistory::default().stats(&wallet))
+    };
+    // Watched markets use their own thresholds, as live
+    let thresholds_for = |trade: &Trade| {
+        if watchlist.is_watched(trade) {
+            Thresholds::watched_market()
+        } else {
+            *thresholds
+        }
+    };
+
+    let mut suspects: Vec<SuspectTrade> = Vec::new();
+    for trade in trades {
+        let title = trade.title.as_deref().unwrap_or("");
+        if !is_gambling_market(title) || watchlist.is_watched(trade) {
+            let live = trade.timestamp >= alerts_from;
+            let stats = stats_of(&history, &trade.proxy_wallet);
+            let t = thresholds_for(trade);
+            let context = context_for(trade, markets);
+            let suppressed = detect::is_suppressed(allowlist, trade);
+            let value_usd = trade.value_usd();
+            let mut found: Vec<SuspectTrade> = Vec::new();
+
+            // Anomaly baselines build during the warmup too
+            let market_anomalies = anomalies.record(trade, &anomaly_params);
+
+            if live {
+                if trade.is_taker_buy()
+                    && trade.price < t.max_price
+                    && value_usd >= cluster_min_trade
+                    && allowlist.lookup(trade).is_none()
+                    && stats.fresh_age(&t, trade.timestamp).is_some()
+                {
+                    if let Some(fills) = clusters.record(trade, &cluster_params) {
+                        let members = cluster_wallets(&fills).iter().map(|w| stats_of(&history, w)).collect();
+                        found.push(detect::cluster_suspect(fills, stats.clone(), members));
+                    }
+                }
+
+                if value_usd >= t.min_trade_size_usd {
+                    if (trade.is_taker_buy() || trade.is_taker_sell())
+                        && trade.effective_price() < t.max_price
+                        && !suppressed
+                        && !detect::covered_by_wallet_alert(&found, trade)
+                    {
+                        found.extend(detect::evaluate_trade(
+                            trade.clone(),
+                            stats.clone(),
+                            &t,
+                            context.as_ref(),
+                            trade.timestamp,
+                        ));
+                    }
+                } else if trade.is_taker_buy() && trade.price < t.max_price && !suppressed {
+                    if let Some(fills) = split_orders.record(trade, split_window, t.min_trade_size_usd) {
+                        found.extend(detect::evaluate_split_order(fills, stats.clone(), &t, trade.timestamp));
+                    }
+                }
+
+                if trade.is_taker_sell() && value_usd >= exit_min && !detect::covered_by_wallet_alert(&found, trade) {
+                    if let Some(level) = flagged.get(&trade.proxy_wallet.to_lowercase()) {
+                        found.push(detect::exit_suspect(trade.clone(), stats.clone(), *level));
+                    }
+                }
+
+                for anomaly in market_anomalies {
+                    if let Some(attributed) = detect::anomaly_trade(&anomaly) {
+                        let attributed_stats = stats_of(&history, &attributed.proxy_wallet);
+                        found.push(detect::anomaly_suspect(anomaly, attributed, attributed_stats));
+                    }
+                }
+
+                if watchlist.alert_any_fresh(trade) && !detect::covered_by_wallet_alert(&found, trade) {
+                    found.extend(detect::evaluate_watched_market(trade.clone(), stats, &t, trade.timestamp));
+                }
+            }
+
+            let mut scored = Vec::new();
+            for mut suspect in found {
+                if suspect.detector.is_wallet_level() {
+                    match allowlist.lookup(&suspect.trade) {
+                        Some(entry) if matches!(entry.action, AllowAction::Suppress) => continue,
+                        Some(entry) => detect::cap_low(&mut suspect, &entry.name),
+                        None => {}
+                    }
+                }
+                let market = suspect.trade.condition_id.as_ref().and_then(|cid| markets.get(cid)).cloned();
+                let context = context_for(&suspect.trade, markets);
+                let t = thresholds_for(&suspect.trade);
+                let watched = watchlist.is_watched(&suspect.trade);
+                scored.extend(detect::score_suspect(suspect, market, context, &t, watched, trade.timestamp));
+            }
+
+            for suspect in scored.iter().filter(|s| s.detector.is_wallet_level()) {
+                let wallets = std::iter::once(&suspect.trade)
+                    .chain(suspect.fills.iter().filter(|_| suspect.detector == Detector::Cluster))
+                    .map(|t| t.proxy_wallet.to_lowercase());
+                for wallet in wallets {
+                    let entry = flagged.entry(wallet).or_insert(suspect.alert_level);
+                    if suspect.alert_level == AlertLevel::High {
+                        *entry = AlertLevel::High;
+                    }
+                }
+            }
+
+            let (admitted, _) = dedup.admit(scored, trade.timestamp);
+            suspects.extend(admitted);
+
+            split_orders.prune(trade.timestamp, split_window);
+            clusters.prune(trade.timestamp, cluster_params.window_secs);
+            anomalies.prune(trade.timestamp, &anomaly_params);
+        }
+
+        let entry = history.entry(trade.proxy_wallet.to_lowercase()).or_default();
+        entry.first_ts.get_or_insert(trade.timestamp);
+        if let Some(key) = trade.market_key() {
+            entry.markets.insert(key);
+        }
+        entry.trades += 1;
+    }
+
+    let mut alerts = Vec::new();
+    let mut outcomes = HashMap::new();
+    for suspect in &suspects {
+        let mut alert = AlertRecord::from_suspect(suspect);
+        alert.created_at = suspect.trade.timestamp;
+        if let Some(outcome) = resolve(&alert, markets) {
+            outcomes.insert(alert.id.clone(), outcome);
+        }
+        alerts.push(alert);
+    }
+    (alerts, outcomes)
+}
+
+/// What a trade is betting on, from the cached market (negRisk siblings are not replayed)
+fn context_for(trade: &Trade, markets: &HashMap<String, Market>) -> Option<BetContext> {
+    let market = markets.get(trade.condition_id.as_deref()?)?;
+    Some(bet_context(trade, market, None))
+}
+
+/// Score a replayed alert against its market's resolution
+fn resolve(alert: &AlertRecord, markets: &HashMap<String, Market>) -> Option<AlertOutcome> {
+    let market = markets.get(alert.condition_id.as_ref()?)?;
+    let mut outcome = AlertOutcome {
+        checked_at: alert.created_at,
+        category: market.category.clone(),
+        ..Default::default()
+    };
+    if let (Some(winner), Some(index)) = (market.winning_outcome_index(), alert_outcome_index(alert, market)) {
+        let won = (winner == index) != alert.side.eq_ignore_ascii_case("SELL");
+        let (pnl_usd, return_pct) = position_result(alert, won);
+        outcome.resolved = true;
+        outcome.resolved_at = market.end_timestamp();
+        outcome.won = Some(won);
+        outcome.winner = market.outcome_labels().get(winner).cloned();
+        outcome.pnl_usd = Some(pnl_usd);
+        outcome.return_pct = Some(return_pct);
+    }
+    Some(outcome)
+}
+
+// ============================================================================
+// DATA
+// ============================================================================
+
+/// Load trades (JSON lines or a JSON array), deduplicated and sorted by time
+fn load_trades(path: &str) -> Result<Vec<Trade>> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let mut trades: Vec<Trade> = if raw.trim_start().starts_with('[') {
+        serde_json::from_str(&raw).with_context(|| format!("Invalid trades JSON in {}", path))?
+    } else {
+        raw.lines()
+            .filter(|l| !l.trim().is_empty())
+            .enumerate()
+            .filter_map(|(i, line)| match serde_json::from_str(line) {
+                Ok(trade) => Some(trade),
+                Err(e) => {
+                    eprintln!("⚠️  Skipping bad line {} in {}: {}", i + 1, path, e);
+                    None
+                }
+            })
+            .collect()
+    };
+
+    let mut seen = HashSet::new();
+    trades.retain(|t| seen.insert(t.unique_id()));
+    trades.sort_by_key(|t| t.timestamp);
+    Ok(trades)
+}
+
+/// Market resolutions for every traded market, cached in DATA_DIR. Markets
+/// not cached as resolved are (re)fetched when `fetch` is set.
+async fn load_resolutions(trades: &[Trade], fetch: bool) -> Result<HashMap<String, Market>> {
+    let mut markets: HashMap<String, Market> = load_json(RESOLUTIONS_FILE)?.unwrap_or_default();
+    if !fetch {
+        return Ok(markets);
+    }
+
+    let missing: Vec<String> = trades
+        .iter()
+        .filter_map(|t| t.condition_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter(|cid| markets.get(cid).is_none_or(|m| m.winning_outcome_index().is_none()))
+        .collect();
+
+    let mut client = ApiClient::new();
+    for chunk in missing.chunks(20) {
+        match client.fetch_markets(chunk).await {
+            Ok(fetched) => {
+                for market in fetched {
+                    if let Some(cid) = market.condition_id.clone() {
+                        markets.insert(cid, market);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("⚠️  Resolution fetch failed, continuing with cached markets: {:#}", e);
+                break;
+            }
+        }
+    }
+    save_json(RESOLUTIONS_FILE, &markets)?;
+    Ok(markets)
+}
+
+// ============================================================================
+// OUTPUT
+// ============================================================================
+
+fn config_label(t: &Thresholds) -> String {
+    format!(
+        "${:.0} | <{:.0}% | ≤{} mkts | <{}h",
+        t.min_trade_size_usd,
+        t.max_price * 100.0,
+        t.max_unique_markets,
+        t.max_wallet_age_hours
+    )
+}
+
+fn print_config(thresholds: &Thresholds, follow_pnl_usd: f64, stake_usd: f64) {
+    println!();
+    println!("⚙️  Config:      {}", config_label(thresholds));
+    println!(
+        "💵 Follow PnL:  {}${:.0} (${:.0} per resolved alert)",
+        if follow_pnl_usd >= 0.0 { "+" } else { "-" },
+        follow_pnl_usd.abs(),
+        stake_usd
+    );
+}
+
+/// Configs side by side, best expected PnL first
+fn print_comparison(runs: &[BacktestRun], stake_usd: f64) {
+    let divider = "═".repeat(65);
+    println!();
+    println!("{}", divider.bright_white());
+    println!("⏪ {} ({} configs, ${:.0} per alert)", "BACKTEST COMPARISON".bold(), runs.len(), stake_usd);
+    println!("{}", divider.bright_white());
+    println!(
+        "  {:<34} {:>6} {:>8} {:>8} {:>9} {:>12}",
+        "min size | odds | markets | age", "alerts", "resolved", "hit rate", "avg ret", "follow PnL"
+    );
+    for run in runs {
+        let stats: &AlertStats = &run.report.overall;
+        println!(
+            "  {:<34} {:>6} {:>8} {:>8} {:>9} {:>12}",
+            config_label(&run.thresholds),
+            stats.alerts,
+            stats.resolved,
+            stats
+                .precision
+                .map(|p| format!("{:.0}%", p * 100.0))
+                .unwrap_or_else(|| "-".to_string()),
+            stats
+                .avg_return
+                .map(|r| format!("{:+.0}%", r * 100.0))
+                .unwrap_or_else(|| "-".to_string()),
+            format!("{}${:.0}", if run.follow_pnl_usd >= 0.0 { "+" } else { "-" }, run.follow_pnl_usd.abs())
+        );
+    }
+    println!("{}", divider.bright_white());
+    println!();
+}
@@ -0,0 +1,369 @@
+//! On-chain funding source tracing
+//!
+//! A burner wallet is best identified by where its USDC came from: a fresh
+//! exchange withdrawal, a mixer, or another wallet we already flagged.
+//! `ChainProvider` abstracts the data source (block explorer indexer, or a
+//! local fixture file for tests and offline runs).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    address_labels_file, chain_fixture_file, chain_indexer_url, chain_provider, polygonscan_api_key,
+    USDC_POLYGON_ADDRESS,
+};
+
+/// An incoming USDC transfer to a wallet
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FundingTransfer {
+    pub from: String,
+    pub amount_usdc: f64,
+    pub timestamp: i64,
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+}
+
+/// What kind of address funded a wallet
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundingSourceKind {
+    Exchange,
+    Mixer,
+    FlaggedWallet,
+    Unknown,
+}
+
+/// Funding facts for a wallet, attached to `UserStats`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WalletFunding {
+    pub transfer: FundingTransfer,
+    pub source_kind: FundingSourceKind,
+    /// Label of the source, e.g. "Binance"
+    pub source_label: Option<String>,
+}
+
+impl WalletFunding {
+    /// e.g. "$20000 from Binance (exchange) 3h before trade"
+    pub fn describe(&self, trade_ts: i64) -> String {
+        let source = match (&self.source_label, &self.source_kind) {
+            (Some(label), FundingSourceKind::Unknown) => label.clone(),
+            (Some(label), kind) => format!("{} ({})", label, kind_name(kind)),
+            (None, FundingSourceKind::Unknown) => crate::api::mask_address(&self.transfer.from),
+            (None, kind) => format!("{} ({})", crate::api::mask_address(&self.transfer.from), kind_name(kind)),
+        };
+        let lead_hours = (trade_ts - self.transfer.timestamp) as f64 / 3600.0;
+        if lead_hours >= 0.0 {
+            format!(
+                "${:.0} from {} {:.0}h before trade",
+                self.transfer.amount_usdc, source, lead_hours
+            )
+        } else {
+            format!("${:.0} from {}", self.transfer.amount_usdc, source)
+        }
+    }
+}
+
+fn kind_name(kind: &FundingSourceKind) -> &'static str {
+    match kind {
+        FundingSourceKind::Exchange => "exchange",
+        FundingSourceKind::Mixer => "mixer",
+        FundingSourceKind::FlaggedWallet => "flagged wallet",
+        FundingSourceKind::Unknown => "unknown",
+    }
+}
+
+/// Known address, loaded from ADDRESS_LABELS_FILE
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddressLabel {
+    pub kind: FundingSourceKind,
+    pub name: String,
+}
+
+/// Load the address label map (lowercased keys). Missing file = no labels.
+pub fn load_address_labels() -> HashMap<String, AddressLabel> {
+    let Some(path) = address_labels_file() else {
+        return HashMap::new();
+    };
+    match std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path))
+        .and_then(|raw| serde_json::from_str::<HashMap<String, AddressLabel>>(&raw).context("Invalid labels JSON"))
+    {
+        Ok(labels) => labels.into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect(),
+        Err(e) => {
+            eprintln!("⚠️  Address labels not loaded: {:#}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Classify a funding transfer by its source address
+pub fn classify_funding(
+    transfer: FundingTransfer,
+    labels: &HashMap<String, AddressLabel>,
+    source_flagged: bool,
+) -> WalletFunding {
+    let label = labels.get(&transfer.from.to_lowercase());
+    let source_kind = if source_flagged {
+        FundingSourceKind::FlaggedWallet
+    } else {
+        label.map(|l| l.kind.clone()).unwrap_or(FundingSourceKind::Unknown)
+    };
+
+    WalletFunding {
+        transfer,
+        source_kind,
+        source_label: label.map(|l| l.name.clone()),
+    }
+}
+
+// ============================================================================
+// PROVIDERS
+// ============================================================================
+
+#[async_trait]
+pub trait ChainProvider: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &str;
+
+    /// The first USDC transfer into `address`, if any
+    async fn first_funding(&self, address: &str) -> Result<Option<FundingTransfer>>;
+}
+
+/// Build the provider selected by CHAIN_PROVIDER (None when disabled)
+pub fn provider_from_config() -> Option<Box<dyn ChainProvider>> {
+    match chain_provider()?.as_str() {
+        "polygonscan" | "indexer" => Some(Box::new(IndexerChainProvider::new(
+            chain_indexer_url(),
+            polygonscan_api_key(),
+        ))),
+        "fixture" => match chain_fixture_file() {
+            Some(path) => match FixtureChainProvider::load(&path) {
+                Ok(provider) => Some(Box::new(provider)),
+                Err(e) => {
+                    eprintln!("⚠️  Chain fixture not loaded: {:#}", e);
+                    None
+                }
+            },
+            None => {
+                eprintln!("⚠️  CHAIN_PROVIDER=fixture needs CHAIN_FIXTURE_FILE");
+                None
+            }
+        },
+        other => {
+            eprintln!("⚠️  Unknown CHAIN_PROVIDER '{}', funding tracing disabled", other);
+            None
+        }
+    }
+}
+
+/// Etherscan-compatible indexer (Polygonscan / Etherscan v2 with chainid=137)
+pub struct IndexerChainProvider {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IndexerResponse {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    result: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexerTokenTx {
+    time_stamp: String,
+    hash: String,
+    from: String,
+    to: String,
+    value: String,
+    token_decimal: String,
+}
+
+impl IndexerChainProvider {
+    pub fn new(base_url: String, api_key: Option<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            client,
+            base_url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl ChainProvider for IndexerChainProvider {
+    fn name(&self) -> &str {
+        "indexer"
+    }
+
+    async fn first_funding(&self, address: &str) -> Result<Option<FundingTransfer>> {
+        let mut url = format!(
+            "{}?chainid=137&module=account&action=tokentx&contractaddress={}&address={}&page=1&offset=20&sort=asc",
+            self.base_url, USDC_POLYGON_ADDRESS, address
+        );
+        if let Some(key) = &self.api_key {
+            url.push_str(&format!("&apikey={}", key));
+        }
+
+        // The URL carries the API key: keep it out of logs
+        let response: IndexerResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| e.without_url())
+            .context("Indexer request failed")?
+            .json()
+            .await
+            .map_err(|e| e.without_url())
+            .context("Indexer returned invalid JSON")?;
+
+        first_incoming(response, address)
+    }
+}
+
+/// The first transfer into `address` from an indexer response. Only an
+/// explicit "No transactions found" means the wallet has none; rate limits
+/// and key errors are errors, not an unfunded wallet.
+fn first_incoming(response: IndexerResponse, address: &str) -> Result<Option<FundingTransfer>> {
+    if response.status == "0" && response.message.eq_ignore_ascii_case("No transactions found") {
+        return Ok(None);
+    }
+    if response.status != "1" {
+        bail!("Indexer error: {} ({})", response.message, response.result);
+    }
+    let txs: Vec<IndexerTokenTx> =
+        serde_json::from_value(response.result).context("Unexpected indexer result")?;
+
+    let incoming = txs
+        .into_iter()
+        .find(|tx| tx.to.eq_ignore_ascii_case(address));
+    Ok(incoming.map(|tx| {
+        let decimals: i32 = tx.token_decimal.parse().unwrap_or(6);
+        let raw: f64 = tx.value.parse().unwrap_or(0.0);
+        FundingTransfer {
+            from: tx.from,
+            amount_usdc: raw / 10f64.powi(decimals),
+            timestamp: tx.time_stamp.parse().unwrap_or(0),
+            tx_hash: Some(tx.hash),
+        }
+    }))
+}
+
+/// Funding transfers from a local JSON file: `{ "0xwallet": { "from": ..., "amount_usdc": ..., "timestamp": ... } }`
+pub struct FixtureChainProvider {
+    transfers: HashMap<String, FundingTransfer>,
+}
+
+impl FixtureChainProvider {
+    pub fn load(path: &str) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let transfers: HashMap<String, FundingTransfer> =
+            serde_json::from_str(&raw).with_context(|| format!("Invalid fixture JSON in {}", path))?;
+        Ok(Self {
+            transfers: transfers
+                .into_iter()
+                .map(|(k, v)| (k.to_lowercase(), v))
+                .collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl ChainProvider for FixtureChainProvider {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    async fn first_funding(&self, address: &str) -> Result<Option<FundingTransfer>> {
+        Ok(self.transfers.get(&address.to_lowercase()).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str, json: &str) -> FixtureChainProvider {
+        let path = std::env::temp_dir().join(format!("chain_fixture_{}_{}.json", name, std::process::id()));
+        std::fs::write(&path, json).unwrap();
+        let provider = FixtureChainProvider::load(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        provider
+    }
+
+    #[tokio::test]
+    async fn fixture_funding_is_classified_by_source() {
+        let provider = fixture(
+            "classify",
+            r#"{
+                "0xBurner": { "from": "0xEXCHANGE", "amount_usdc": 20000.0, "timestamp": 1000 },
+                "0xsecond": { "from": "0xburner", "amount_usdc": 500.0, "timestamp": 2000 }
+            }"#,
+        );
+        let labels: HashMap<String, AddressLabel> = [(
+            "0xexchange".to_string(),
+            AddressLabel {
+                kind: FundingSourceKind::Exchange,
+                name: "Binance".to_string(),
+            },
+        )]
+        .into();
+
+        // Addresses match case-insensitively, in the fixture and the labels
+        let transfer = provider.first_funding("0xburner").await.unwrap().unwrap();
+        let funding = classify_funding(transfer, &labels, false);
+        assert_eq!(funding.source_kind, FundingSourceKind::Exchange);
+        assert_eq!(funding.source_label.as_deref(), Some("Binance"));
+        assert_eq!(funding.describe(1000 + 3 * 3600), "$20000 from Binance (exchange) 3h before trade");
+
+        // A flagged source wins over its label
+        let transfer = provider.first_funding("0xBURNER").await.unwrap().unwrap();
+        let funding = classify_funding(transfer, &labels, true);
+        assert_eq!(funding.source_kind, FundingSourceKind::FlaggedWallet);
+
+        let transfer = provider.first_funding("0xsecond").await.unwrap().unwrap();
+        let funding = classify_funding(transfer, &labels, false);
+        assert_eq!(funding.source_kind, FundingSourceKind::Unknown);
+        assert_eq!(funding.source_label, None);
+
+        assert!(provider.first_funding("0xunknown").await.unwrap().is_none());
+    }
+
+    fn response(json: &str) -> IndexerResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn indexer_errors_are_not_an_unfunded_wallet() {
+        let none = response(r#"{"status":"0","message":"No transactions found","result":[]}"#);
+        assert!(first_incoming(none, "0xabc").unwrap().is_none());
+
+        let rate_limited = response(r#"{"status":"0","message":"NOTOK","result":"Max rate limit reached"}"#);
+        assert!(first_incoming(rate_limited, "0xabc").is_err());
+
+        let found = response(
+            r#"{"status":"1","message":"OK","result":[
+                {"timeStamp":"50","hash":"0x1","from":"0xabc","to":"0xother","value":"1","tokenDecimal":"6"},
+                {"timeStamp":"100","hash":"0x2","from":"0xsrc","to":"0xABC","value":"2500000","tokenDecimal":"6"}
+            ]}"#,
+        );
+        let transfer = first_incoming(found, "0xabc").unwrap().unwrap();
+        assert_eq!(transfer.from, "0xsrc");
+        assert_eq!(transfer.amount_usdc, 2.5);
+        assert_eq!(transfer.timestamp, 100);
+    }
+}
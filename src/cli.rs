@@ -0,0 +1,146 @@
+//! Command-line subcommands
+//!
+//! With no arguments the tracker runs the live monitor. Subcommands:
+//!   wallet <address> [--json]   Print a wallet's full profile
+//!   watch add|remove <address>  Manage the wallet watchlist
+//!   watch list
+//!   watch-market add <condition_id|event_slug> [--any-fresh]
+//!   watch-market remove <condition_id|event_slug>
+//!   watch-market list
+//!   report [--json] [--no-refresh]   Alert precision and returns
+//!   backtest [options]               Replay stored trades, sweeping a parameter grid
+//!   dead-letters [replay]            List or resend alerts that could not be delivered
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::backtest::{BacktestArgs, ParamGrid};
+use crate::market_watchlist::MarketWatchAction;
+use crate::watchlist::WatchAction;
+
+pub enum Command {
+    /// Live trade monitoring (default)
+    Run,
+    /// Full profile of one wallet
+    Wallet { address: String, json: bool },
+    /// Manage the wallet watchlist
+    Watch(WatchAction),
+    /// Manage the market watchlist
+    WatchMarket(MarketWatchAction),
+    /// Alert precision report; `refresh` follows up on pending alerts first
+    Report { json: bool, refresh: bool },
+    /// Replay stored trades through the detectors
+    Backtest(BacktestArgs),
+    /// List undelivered alerts, or resend them with `replay`
+    DeadLetters { replay: bool },
+}
+
+/// Options that take a value (`--trades data/trades.jsonl`)
+const VALUE_OPTIONS: &[&str] = &[
+    "--trades",
+    "--min-size",
+    "--max-price",
+    "--max-markets",
+    "--max-age-hours",
+    "--warmup-hours",
+    "--stake",
+];
+
+pub fn parse_args() -> Result<Command> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().any(|a| a == "--json");
+    let any_fresh = args.iter().any(|a| a == "--any-fresh");
+    let mut options: HashMap<&str, &str> = HashMap::new();
+    let mut positional: Vec<&str> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            let value = iter.next().ok_or_else(|| anyhow!("{} needs a value", arg))?;
+            options.insert(arg.as_str(), value.as_str());
+        } else if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+        }
+    }
+
+    match positional.as_slice() {
+        [] | ["run"] => Ok(Command::Run),
+        ["wallet", address] => Ok(Command::Wallet {
+            address: address.to_string(),
+            json,
+        }),
+        ["wallet"] => Err(anyhow!("Usage: wallet <address> [--json]")),
+        ["watch", "add", address] => Ok(Command::Watch(WatchAction::Add(address.to_string()))),
+        ["watch", "remove", address] => Ok(Command::Watch(WatchAction::Remove(address.to_string()))),
+        ["watch", "list"] | ["watch"] => Ok(Command::Watch(WatchAction::List)),
+        ["watch", ..] => Err(anyhow!("Usage: watch add|remove <address> | watch list")),
+        ["watch-market", "add", key] => Ok(Command::WatchMarket(MarketWatchAction::Add {
+            key: key.to_string(),
+            alert_any_fresh: any_fresh,
+        })),
+        ["watch-market", "remove", key] => Ok(Command::WatchMarket(MarketWatchAction::Remove(key.to_string()))),
+        ["watch-market", "list"] | ["watch-market"] => Ok(Command::WatchMarket(MarketWatchAction::List)),
+        ["watch-market", ..] => Err(anyhow!(
+            "Usage: watch-market add <condition_id|event_slug> [--any-fresh] | watch-market remove <key> | watch-market list"
+        )),
+        ["report"] => Ok(Command::Report {
+            json,
+            refresh: !args.iter().any(|a| a == "--no-refresh"),
+        }),
+        ["backtest"] => Ok(Command::Backtest(BacktestArgs {
+            trades_file: options.get("--trades").map(|s| s.to_string()),
+            grid: ParamGrid {
+                min_size: parse_list(&options, "--min-size")?,
+                max_price: parse_list(&options, "--max-price")?,
+                max_markets: parse_list(&options, "--max-markets")?,
+                max_age_hours: parse_list(&options, "--max-age-hours")?,
+            },
+            warmup_hours: parse_value(&options, "--warmup-hours")?.unwrap_or(24),
+            stake_usd: parse_value(&options, "--stake")?.unwrap_or(100.0),
+            fetch_resolutions: !args.iter().any(|a| a == "--offline"),
+            json,
+        })),
+        ["dead-letters"] | ["dead-letters", "list"] => Ok(Command::DeadLetters { replay: false }),
+        ["dead-letters", "replay"] => Ok(Command::DeadLetters { replay: true }),
+        ["dead-letters", ..] => Err(anyhow!("Usage: dead-letters [list|replay]")),
+        [other, ..] => Err(anyhow!("Unknown command '{}'\n\n{}", other, USAGE)),
+    }
+}
+
+pub const USAGE: &str = "Usage:
+  polymarket-insider-tracker                     Run the live tracker
+  polymarket-insider-tracker wallet <address> [--json]
+  polymarket-insider-tracker watch add|remove <address>
+  polymarket-insider-tracker watch list
+  polymarket-insider-tracker watch-market add <condition_id|event_slug> [--any-fresh]
+  polymarket-insider-tracker watch-market remove <condition_id|event_slug>
+  polymarket-insider-tracker watch-market list
+  polymarket-insider-tracker report [--json] [--no-refresh]
+  polymarket-insider-tracker backtest [--trades <file>] [--min-size 1000,5000] [--max-price 0.2,0.35]
+                                      [--max-markets 2,5] [--max-age-hours 24,72] [--warmup-hours 24]
+                                      [--stake 100] [--offline] [--json]
+  polymarket-insider-tracker dead-letters [list|replay]";
+
+fn parse_value<T: FromStr>(options: &HashMap<&str, &str>, name: &str) -> Result<Option<T>>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    options
+        .get(name)
+        .map(|v| v.parse().with_context(|| format!("Invalid value for {}: {}", name, v)))
+        .transpose()
+}
+
+/// Comma-separated values of an option (empty if not given)
+fn parse_list<T: FromStr>(options: &HashMap<&str, &str>, name: &str) -> Result<Vec<T>>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let Some(raw) = options.get(name) else {
+        return Ok(vec![]);
+    };
+    raw.split(',')
+        .map(|v| v.trim().parse().with_context(|| format!("Invalid value for {}: {}", name, v)))
+        .collect()
+}
@@ -0,0 +1,127 @@
+//! Coordinated multi-wallet cluster detection
+//!
+//! The Maduro case was $630k from 3 new wallets on the same outcome within
+//! hours. Each wallet alone may look unremarkable; this groups taker BUYs by
+//! fresh wallets per (market, outcome) and fires once the group is big enough.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::types::Trade;
+
+/// (condition_id, outcome)
+type OutcomeKey = (String, String);
+
+/// Thresholds for a cluster alert
+pub struct ClusterParams {
+    pub min_wallets: usize,
+    pub window_secs: i64,
+    pub min_value_usd: f64,
+}
+
+/// Rolling buckets of fresh-wallet buys per market outcome
+pub struct ClusterDetector {
+    buckets: HashMap<OutcomeKey, Vec<Trade>>,
+}
+
+impl ClusterDetector {
+    pub fn new() -> Self {
+        Self {
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Record a taker BUY from a fresh wallet. Returns every trade in the
+    /// cluster (oldest first) once enough distinct wallets with enough
+    /// combined value bought the same outcome inside the window.
+    pub fn record(&mut self, trade: &Trade, params: &ClusterParams) -> Option<Vec<Trade>> {
+        let key = outcome_key(trade)?;
+        let trades = self.buckets.entry(key.clone()).or_default();
+
+        trades.retain(|t| trade.timestamp - t.timestamp <= params.window_secs);
+        trades.push(trade.clone());
+
+        let wallets: HashSet<String> = trades.iter().map(|t| t.proxy_wallet.to_lowercase()).collect();
+        let total: f64 = trades.iter().map(|t| t.value_usd()).sum();
+
+        if wallets.len() >= params.min_wallets && total >= params.min_value_usd {
+            return self.buckets.remove(&key);
+        }
+
+        None
+    }
+
+    /// Forget outcomes with no buys inside the window
+    pub fn prune(&mut self, now_ts: i64, window_secs: i64) {
+        self.buckets.retain(|_, trades| {
+            trades
+                .last()
+                .map(|t| now_ts - t.timestamp <= window_secs)
+                .unwrap_or(false)
+        });
+    }
+}
+
+/// Distinct wallets in a cluster, in order of first appearance
+pub fn cluster_wallets(trades: &[Trade]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    trades
+        .iter()
+        .filter(|t| seen.insert(t.proxy_wallet.to_lowercase()))
+        .map(|t| t.proxy_wallet.clone())
+        .collect()
+}
+
+fn outcome_key(trade: &Trade) -> Option<OutcomeKey> {
+    Some((trade.market_key()?, trade.outcome_key()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::trade;
+
+    const PARAMS: ClusterParams = ClusterParams {
+        min_wallets: 3,
+        window_secs: 3600,
+        min_value_usd: 10_000.0,
+    };
+
+    /// A $5,000 taker BUY by `wallet` at `timestamp`
+    fn buy(wallet: &str, timestamp: i64) -> Trade {
+        trade(wallet, "BUY", 0.25, 20_000.0, timestamp)
+    }
+
+    #[test]
+    fn repeat_fills_from_one_wallet_count_once() {
+        let mut detector = ClusterDetector::new();
+        assert!(detector.record(&buy("0xa", 0), &PARAMS).is_none());
+        assert!(detector.record(&buy("0xA", 10), &PARAMS).is_none());
+        assert!(detector.record(&buy("0xa", 20), &PARAMS).is_none());
+        assert!(detector.record(&buy("0xb", 30), &PARAMS).is_none());
+
+        let trades = detector.record(&buy("0xc", 40), &PARAMS).unwrap();
+        assert_eq!(trades.len(), 5);
+        assert_eq!(cluster_wallets(&trades), vec!["0xa", "0xb", "0xc"]);
+
+        // Fires once: the bucket starts over
+        assert!(detector.record(&buy("0xd", 50), &PARAMS).is_none());
+    }
+
+    #[test]
+    fn wallets_outside_the_window_do_not_count() {
+        let mut detector = ClusterDetector::new();
+        assert!(detector.record(&buy("0xa", 0), &PARAMS).is_none());
+        assert!(detector.record(&buy("0xb", 1800), &PARAMS).is_none());
+        assert!(detector.record(&buy("0xc", 3601), &PARAMS).is_none());
+        assert!(detector.record(&buy("0xd", 3700), &PARAMS).is_some());
+    }
+
+    #[test]
+    fn enough_wallets_but_too_little_value_does_not_fire() {
+        let mut detector = ClusterDetector::new();
+        for (i, wallet) in ["0xa", "0xb", "0xc", "0xd"].into_iter().enumerate() {
+            let small = trade(wallet, "BUY", 0.25, 4_000.0, i as i64);
+            assert!(detector.record(&small, &PARAMS).is_none());
+        }
+    }
+}
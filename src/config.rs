@@ -2,6 +2,8 @@
 
 use std::env;
 
+use serde::Serialize;
+
 // ============================================================================
 // INSIDER DETECTION THRESHOLDS
 // ============================================================================
@@ -38,6 +40,168 @@ pub fn max_price_threshold() -> f64 {
         .unwrap_or(0.35)  // < 35% odds = contrarian
 }
 
+/// Minimum sell size for a previously flagged wallet's exit to alert
+pub fn exit_min_value_usd() -> f64 {
+    env::var("EXIT_MIN_VALUE_USD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1000.0)
+}
+
+/// Rolling window for summing split fills into one position (seconds)
+pub fn split_order_window_secs() -> i64 {
+    env::var("SPLIT_ORDER_WINDOW_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(900)  // 15 minutes
+}
+
+// ============================================================================
+// TRACK RECORD - Low-volume wallets that keep hitting long shots
+// ============================================================================
+
+/// Only wallets with at most this many markets get a track record computed
+pub fn record_max_markets() -> usize {
+    env::var("RECORD_MAX_MARKETS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(20)
+}
+
+/// Resolved long-shot bets needed before the hit rate means anything
+pub fn record_min_longshots() -> usize {
+    env::var("RECORD_MIN_LONGSHOTS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3)
+}
+
+/// Long-shot hit rate that counts as implausible
+pub fn record_min_longshot_hit_rate() -> f64 {
+    env::var("RECORD_MIN_LONGSHOT_HIT_RATE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.6)
+}
+
+// ============================================================================
+// CLUSTER DETECTION - Several fresh wallets on the same outcome
+// ============================================================================
+
+/// Minimum distinct fresh wallets buying the same outcome to form a cluster
+/// (at least 2: a single wallet is not a cluster)
+pub fn cluster_min_wallets() -> usize {
+    env::var("CLUSTER_MIN_WALLETS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3)
+        .max(2)
+}
+
+/// Window in which cluster buys must land (seconds)
+pub fn cluster_window_secs() -> i64 {
+    env::var("CLUSTER_WINDOW_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600)  // 1 hour
+}
+
+/// Minimum combined USD value of the cluster
+pub fn cluster_min_value_usd() -> f64 {
+    env::var("CLUSTER_MIN_VALUE_USD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10000.0)
+}
+
+/// Minimum single trade size considered for clustering (ignores dust)
+pub fn cluster_min_trade_usd() -> f64 {
+    env::var("CLUSTER_MIN_TRADE_USD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(500.0)
+}
+
+// ============================================================================
+// PRE-RESOLUTION TIMING - Bets placed just before scheduled resolution
+// ============================================================================
+
+/// Window before a market's scheduled end date that counts as "pre-resolution"
+pub fn pre_resolution_window_hours() -> f64 {
+    env::var("PRE_RESOLUTION_WINDOW_HOURS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(72.0)
+}
+
+/// Only alert on trades placed inside the pre-resolution window
+pub fn pre_resolution_only() -> bool {
+    env::var("PRE_RESOLUTION_ONLY")
+        .map(|s| s == "true" || s == "1")
+        .unwrap_or(false)
+}
+
+// ============================================================================
+// MARKET ANOMALIES - Volume spikes and price drift, any wallet
+// ============================================================================
+
+/// Width of a volume bucket in seconds
+pub fn anomaly_bucket_secs() -> i64 {
+    env::var("ANOMALY_BUCKET_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(300)  // 5 minutes
+        .max(1)  // buckets are aligned with rem_euclid, which panics on 0
+}
+
+/// Number of past buckets forming the volume baseline
+pub fn anomaly_baseline_buckets() -> usize {
+    env::var("ANOMALY_BASELINE_BUCKETS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(24)  // 2 hours of 5-minute buckets
+}
+
+/// Z-score above the baseline that counts as a volume spike
+pub fn anomaly_volume_zscore() -> f64 {
+    env::var("ANOMALY_VOLUME_ZSCORE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(4.0)
+}
+
+/// Minimum taker-buy volume in one bucket before it can be a spike
+pub fn anomaly_min_volume_usd() -> f64 {
+    env::var("ANOMALY_MIN_VOLUME_USD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10000.0)
+}
+
+/// Absolute price move (0-1) that counts as drift
+pub fn price_drift_threshold() -> f64 {
+    env::var("PRICE_DRIFT_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.15)  // 15 percentage points
+}
+
+/// Window over which price drift is measured (seconds)
+pub fn price_drift_window_secs() -> i64 {
+    env::var("PRICE_DRIFT_WINDOW_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600)
+}
+
+/// Trades at each end of the drift window that must agree on the move
+pub fn price_drift_min_trades() -> usize {
+    env::var("PRICE_DRIFT_MIN_TRADES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5)
+}
+
 /// Polling interval in milliseconds
 pub fn poll_interval_ms() -> u64 {
     env::var("POLL_INTERVAL_MS")
@@ -80,13 +244,79 @@ pub fn is_gambling_market(title: &str) -> bool {
 }
 
 // ============================================================================
-// TELEGRAM / DISCORD
+// TELEGRAM / DISCORD / SLACK / WEBHOOK
 // ============================================================================
 
 pub fn discord_webhook_url() -> Option<String> {
     env::var("DISCORD_WEBHOOK_URL").ok().filter(|s| !s.is_empty())
 }
 
+pub fn slack_webhook_url() -> Option<String> {
+    env::var("SLACK_WEBHOOK_URL").ok().filter(|s| !s.is_empty())
+}
+
+/// Generic JSON webhook for downstream services
+pub fn webhook_url() -> Option<String> {
+    env::var("WEBHOOK_URL").ok().filter(|s| !s.is_empty())
+}
+
+/// Key for the webhook's HMAC-SHA256 signature header
+pub fn webhook_secret() -> Option<String> {
+    env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty())
+}
+
+// ============================================================================
+// EMAIL - SMTP alert delivery
+// ============================================================================
+
+pub fn smtp_host() -> Option<String> {
+    env::var("SMTP_HOST").ok().filter(|s| !s.is_empty())
+}
+
+pub fn smtp_port() -> u16 {
+    env::var("SMTP_PORT")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(587)
+}
+
+/// starttls, tls or none
+pub fn smtp_tls() -> String {
+    env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string())
+}
+
+pub fn smtp_username() -> Option<String> {
+    env::var("SMTP_USERNAME").ok().filter(|s| !s.is_empty())
+}
+
+pub fn smtp_password() -> Option<String> {
+    env::var("SMTP_PASSWORD").ok().filter(|s| !s.is_empty())
+}
+
+pub fn email_from() -> Option<String> {
+    env::var("EMAIL_FROM").ok().filter(|s| !s.is_empty())
+}
+
+/// Comma-separated recipients
+pub fn email_to() -> Vec<String> {
+    env::var("EMAIL_TO")
+        .map(|s| {
+            s.split(',')
+                .map(|addr| addr.trim().to_string())
+                .filter(|addr| !addr.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Collect alerts into one email per window (seconds, 0 = one email per alert)
+pub fn email_batch_secs() -> u64 {
+    env::var("EMAIL_BATCH_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0)
+}
+
 pub fn telegram_bot_token() -> Option<String> {
     env::var("TELEGRAM_BOT_TOKEN").ok().filter(|s| !s.is_empty())
 }
@@ -95,8 +325,313 @@ pub fn telegram_chat_id() -> Option<String> {
     env::var("TELEGRAM_CHAT_ID").ok().filter(|s| !s.is_empty())
 }
 
-pub fn telegram_enabled() -> bool {
-    telegram_bot_token().is_some() && telegram_chat_id().is_some()
+/// JSON file listing alert sinks; without it, sinks come from the variables above
+pub fn notifiers_file() -> Option<String> {
+    env::var("NOTIFIERS_FILE").ok().filter(|s| !s.is_empty())
+}
+
+/// Directory of `<name>.hbs` files overriding the built-in alert templates
+pub fn templates_dir() -> Option<String> {
+    env::var("TEMPLATES_DIR").ok().filter(|s| !s.is_empty())
+}
+
+/// Delivery attempts per sink before an alert goes to the dead-letter file
+pub fn alert_max_attempts() -> u32 {
+    env::var("ALERT_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(6)
+}
+
+/// First retry delay; doubles on each further failure (seconds)
+pub fn alert_retry_base_secs() -> u64 {
+    env::var("ALERT_RETRY_BASE_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5)
+}
+
+/// Upper bound on the retry delay (seconds)
+pub fn alert_retry_max_secs() -> u64 {
+    env::var("ALERT_RETRY_MAX_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(900)  // 15 minutes
+}
+
+/// Repeat alerts on a position within this long of its last activity are
+/// merged into the original alert (seconds, 0 disables)
+pub fn alert_cooldown_secs() -> i64 {
+    env::var("ALERT_COOLDOWN_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600)  // 1 hour
+}
+
+/// Minimum time between updates about one grown position (seconds)
+pub fn alert_update_interval_secs() -> i64 {
+    env::var("ALERT_UPDATE_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(300)  // 5 minutes
+}
+
+// ============================================================================
+// WALLET LINKAGE - Same operator behind several wallets
+// ============================================================================
+
+/// Max seconds between two wallets' buys of the same outcome to count as co-trading
+pub fn link_co_trade_secs() -> i64 {
+    env::var("LINK_CO_TRADE_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5)
+}
+
+/// Co-trades needed before two wallets are linked
+pub fn link_min_co_trades() -> usize {
+    env::var("LINK_MIN_CO_TRADES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3)
+}
+
+// ============================================================================
+// CHAIN DATA - Wallet funding source tracing
+// ============================================================================
+
+/// Chain data provider: "indexer" (Polygonscan-compatible) or "fixture"
+pub fn chain_provider() -> Option<String> {
+    env::var("CHAIN_PROVIDER").ok().filter(|s| !s.is_empty())
+}
+
+/// Etherscan-compatible API base used by the indexer provider
+pub fn chain_indexer_url() -> String {
+    env::var("CHAIN_INDEXER_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "https://api.etherscan.io/v2/api".to_string())
+}
+
+pub fn polygonscan_api_key() -> Option<String> {
+    env::var("POLYGONSCAN_API_KEY").ok().filter(|s| !s.is_empty())
+}
+
+/// JSON file of funding transfers for the fixture provider
+pub fn chain_fixture_file() -> Option<String> {
+    env::var("CHAIN_FIXTURE_FILE").ok().filter(|s| !s.is_empty())
+}
+
+/// JSON file labelling known addresses (exchanges, mixers)
+pub fn address_labels_file() -> Option<String> {
+    env::var("ADDRESS_LABELS_FILE").ok().filter(|s| !s.is_empty())
+}
+
+/// Bridged USDC (USDC.e) on Polygon, the collateral Polymarket uses
+pub const USDC_POLYGON_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+
+// ============================================================================
+// WATCHLIST - Wallets whose every trade is alerted
+// ============================================================================
+
+/// Comma-separated wallets always watched, in addition to `watch add`
+pub fn watchlist_wallets() -> Vec<String> {
+    env::var("WATCHLIST_WALLETS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Automatically watch wallets behind HIGH alerts
+pub fn watchlist_auto_add() -> bool {
+    env::var("WATCHLIST_AUTO_ADD")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(true)
+}
+
+// ============================================================================
+// MARKET WATCHLIST - Insider-prone markets get a more sensitive threshold set
+// ============================================================================
+
+/// Comma-separated condition IDs or event slugs always watched
+pub fn watchlist_markets() -> Vec<String> {
+    env::var("WATCHLIST_MARKETS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|m| m.trim().to_lowercase())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// Alert on any fresh-wallet trade in markets from WATCHLIST_MARKETS
+pub fn watched_market_alert_any_fresh() -> bool {
+    env::var("WATCHED_MARKET_ALERT_ANY_FRESH")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+}
+
+/// Minimum position size in watched markets
+pub fn watched_market_min_trade_size_usd() -> f64 {
+    env::var("WATCHED_MARKET_MIN_TRADE_SIZE_USD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1000.0)
+}
+
+/// Contrarian odds threshold in watched markets
+pub fn watched_market_max_price_threshold() -> f64 {
+    env::var("WATCHED_MARKET_MAX_PRICE_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.50)
+}
+
+/// Fresh-wallet market count in watched markets
+pub fn watched_market_max_unique_markets() -> usize {
+    env::var("WATCHED_MARKET_MAX_UNIQUE_MARKETS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5)
+}
+
+/// Fresh-wallet age in watched markets (hours)
+pub fn watched_market_max_wallet_age_hours() -> u64 {
+    env::var("WATCHED_MARKET_MAX_WALLET_AGE_HOURS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(168)
+}
+
+/// Filter thresholds, either the standard set or the watched-market set
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Thresholds {
+    pub min_trade_size_usd: f64,
+    pub max_price: f64,
+    pub max_unique_markets: usize,
+    pub max_wallet_age_hours: u64,
+}
+
+impl Thresholds {
+    pub fn standard() -> Self {
+        Self {
+            min_trade_size_usd: min_trade_size_usd(),
+            max_price: max_price_threshold(),
+            max_unique_markets: max_unique_markets(),
+            max_wallet_age_hours: max_wallet_age_hours(),
+        }
+    }
+
+    /// WATCHED_MARKET_* overrides, defaulting to a looser version of each filter
+    pub fn watched_market() -> Self {
+        Self {
+            min_trade_size_usd: watched_market_min_trade_size_usd(),
+            max_price: watched_market_max_price_threshold(),
+            max_unique_markets: watched_market_max_unique_markets(),
+            max_wallet_age_hours: watched_market_max_wallet_age_hours(),
+        }
+    }
+}
+
+// ============================================================================
+// ALLOWLIST - Known market makers and funds
+// ============================================================================
+
+/// JSON file of allowlisted wallets and pseudonyms
+pub fn allowlist_file() -> Option<String> {
+    env::var("ALLOWLIST_FILE").ok().filter(|s| !s.is_empty())
+}
+
+/// Suppress alerts on wallets that behave like market makers
+pub fn market_maker_detection() -> bool {
+    env::var("MARKET_MAKER_DETECTION")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(true)
+}
+
+/// Fills needed before a wallet can be judged a market maker
+pub fn market_maker_min_fills() -> usize {
+    env::var("MARKET_MAKER_MIN_FILLS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(50)
+}
+
+/// Share of fills on the maker side
+pub fn market_maker_min_maker_ratio() -> f64 {
+    env::var("MARKET_MAKER_MIN_MAKER_RATIO")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.8)
+}
+
+/// Share of traded assets the wallet both bought and sold
+pub fn market_maker_min_two_sided_ratio() -> f64 {
+    env::var("MARKET_MAKER_MIN_TWO_SIDED_RATIO")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0.5)
+}
+
+// ============================================================================
+// ALERT FOLLOW-UP - Did our alerts turn out right?
+// ============================================================================
+
+/// How often the live tracker follows up on past alerts (seconds)
+pub fn followup_interval_secs() -> u64 {
+    env::var("FOLLOWUP_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600)
+}
+
+/// Alerts the live tracker follows up per pass; the rest wait for later passes
+pub fn followup_max_alerts() -> usize {
+    env::var("FOLLOWUP_MAX_ALERTS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(200)
+}
+
+// ============================================================================
+// DIGEST - Scheduled summaries
+// ============================================================================
+
+/// "hourly", "daily" or a number of seconds; unset disables digests
+pub fn digest_interval() -> Option<String> {
+    env::var("DIGEST_INTERVAL").ok().filter(|s| !s.is_empty())
+}
+
+/// Sinks that get digests, by name as listed at startup (empty = all)
+pub fn digest_sinks() -> Vec<String> {
+    env::var("DIGEST_SINKS")
+        .map(|s| {
+            s.split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// STORAGE
+// ============================================================================
+
+/// Append every fetched trade to `DATA_DIR/trades.jsonl` for backtesting
+pub fn record_trades() -> bool {
+    env::var("RECORD_TRADES")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+}
+
+/// Directory for persisted state (wallet graph, alerts, ...)
+pub fn data_dir() -> String {
+    env::var("DATA_DIR")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "data".to_string())
 }
 
 // ============================================================================
@@ -104,6 +639,8 @@ pub fn telegram_enabled() -> bool {
 // ============================================================================
 
 pub const DATA_API_BASE: &str = "https://data-api.polymarket.com";
+pub const GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
+pub const CLOB_API_BASE: &str = "https://clob.polymarket.com";
 
 // Rate limiting
 pub const INITIAL_BACKOFF_MS: u64 = 1000;
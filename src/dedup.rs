@@ -0,0 +1,259 @@
+//! Alert deduplication across polls
+//!
+//! A wallet buying the same outcome ten times in a minute is one story, not
+//! ten. Single-wallet alerts are keyed on (wallet, market, outcome); a repeat
+//! within ALERT_COOLDOWN_SECS of the position's last activity is not sent
+//! again but merged into the original alert, and the grown position is
+//! reported as an update ("position now $42k across 7 fills") at most every
+//! ALERT_UPDATE_INTERVAL_SECS. A higher alert level, or a trade on the other
+//! side (an exit), still goes out as a new alert. Open positions live in
+//! `DATA_DIR/open_alerts.json`, so a restart doesn't re-alert them.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{alert_cooldown_secs, alert_update_interval_secs};
+use crate::storage::{load_json, save_json};
+use crate::types::{AlertLevel, Detector, SuspectTrade, Trade};
+
+const OPEN_ALERTS_FILE: &str = "open_alerts.json";
+
+/// Position changes below this are not worth an update (USD)
+const MIN_UPDATE_USD: f64 = 1.0;
+
+/// An alerted position still within its cooldown
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OpenAlert {
+    /// The alert as sent, with every follow-up fill merged in
+    pub suspect: SuspectTrade,
+    pub alerted_at: i64,
+    /// Last time a repeat was merged in
+    pub last_seen_at: i64,
+    /// Position value as last reported to the sinks
+    pub reported_value_usd: f64,
+    pub reported_at: i64,
+}
+
+/// A grown position, reported against its original alert
+#[derive(Debug, Clone)]
+pub struct AlertUpdate {
+    /// The original alert (same ID) with every fill so far
+    pub suspect: SuspectTrade,
+    /// Position value in the previous alert or update
+    pub previous_value_usd: f64,
+    pub alerted_at: i64,
+}
+
+#[derive(Default)]
+pub struct AlertDedup {
+    /// Keyed by "wallet|market|outcome"
+    open: HashMap<String, OpenAlert>,
+    dirty: bool,
+}
+
+impl AlertDedup {
+    /// Load the positions still open from the last run
+    pub fn load() -> Self {
+        match load_json(OPEN_ALERTS_FILE) {
+            Ok(open) => Self {
+                open: open.unwrap_or_default(),
+                dirty: false,
+            },
+            Err(e) => {
+                eprintln!("⚠️  Open alerts not loaded, starting empty: {:#}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Persist if anything changed since the last save
+    pub fn save_if_dirty(&mut self) -> Result<()> {
+        if self.dirty {
+            save_json(OPEN_ALERTS_FILE, &self.open)?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Keep the suspects that deserve a new alert; repeats of an open
+    /// position are merged into it instead. Returns (new alerts, repeats merged).
+    pub fn admit(&mut self, suspects: Vec<SuspectTrade>, now_ts: i64) -> (Vec<SuspectTrade>, usize) {
+        let cooldown = alert_cooldown_secs();
+        if cooldown <= 0 {
+            return (suspects, 0);
+        }
+
+        let before = self.open.len();
+        self.open.retain(|_, open| now_ts - open.last_seen_at <= cooldown);
+        self.dirty |= self.open.len() != before;
+
+        let mut fresh = Vec::new();
+        let mut merged = 0;
+        for suspect in suspects {
+            let Some(key) = dedup_key(&suspect) else {
+                fresh.push(suspect);
+                continue;
+            };
+            if let Some(open) = self.open.get_mut(&key) {
+                if is_repeat(&open.suspect, &suspect) {
+                    merge_fills(&mut open.suspect, &suspect);
+                    open.last_seen_at = now_ts;
+                    self.dirty = true;
+                    merged += 1;
+                    continue;
+                }
+            }
+            self.open.insert(
+                key,
+                OpenAlert {
+                    suspect: suspect.clone(),
+                    alerted_at: now_ts,
+                    last_seen_at: now_ts,
+                    reported_value_usd: suspect.position_value_usd(),
+                    reported_at: now_ts,
+                },
+            );
+            self.dirty = true;
+            fresh.push(suspect);
+        }
+        (fresh, merged)
+    }
+
+    /// Positions that grew since they were last reported and are due an update
+    pub fn take_updates(&mut self, now_ts: i64) -> Vec<AlertUpdate> {
+        let interval = alert_update_interval_secs();
+        let mut updates = Vec::new();
+        for open in self.open.values_mut() {
+            let value = open.suspect.position_value_usd();
+            if value - open.reported_value_usd < MIN_UPDATE_USD || now_ts - open.reported_at < interval {
+                continue;
+            }
+            updates.push(AlertUpdate {
+                suspect: open.suspect.clone(),
+                previous_value_usd: open.reported_value_usd,
+                alerted_at: open.alerted_at,
+            });
+            open.reported_value_usd = value;
+            open.reported_at = now_ts;
+            self.dirty = true;
+        }
+        updates
+    }
+}
+
+/// Position key of a single-wallet alert. Market-wide anomalies and clusters
+/// (whose fills belong to several wallets) aren't deduplicated.
+fn dedup_key(suspect: &SuspectTrade) -> Option<String> {
+    if !suspect.detector.is_wallet_level() || suspect.detector == Detector::Cluster {
+        return None;
+    }
+    let trade = &suspect.trade;
+    Some(format!(
+        "{}|{}|{}",
+        trade.proxy_wallet.to_lowercase(),
+        trade.market_key()?,
+        trade.outcome_key()
+    ))
+}
+
+fn severity(level: AlertLevel) -> u8 {
+    match level {
+        AlertLevel::High => 2,
+        AlertLevel::Medium => 1,
+        AlertLevel::Low => 0,
+    }
+}
+
+/// Same side of the same position, and nothing more alarming than the original
+fn is_repeat(open: &SuspectTrade, suspect: &SuspectTrade) -> bool {
+    open.trade.side.eq_ignore_ascii_case(&suspect.trade.side)
+        && severity(suspect.alert_level) <= severity(open.alert_level)
+}
+
+/// Add a repeat's fills (or its single trade) to the open alert, oldest first
+fn merge_fills(open: &mut SuspectTrade, repeat: &SuspectTrade) {
+    if open.fills.is_empty() {
+        open.fills.push(open.trade.clone());
+    }
+    let incoming: &[Trade] = if repeat.fills.is_empty() {
+        std::slice::from_ref(&repeat.trade)
+    } else {
+        &repeat.fills
+    };
+    for fill in incoming {
+        let id = fill.unique_id();
+        if !open.fills.iter().any(|f| f.unique_id() == id) {
+            open.fills.push(fill.clone());
+        }
+    }
+    open.fills.sort_by_key(|f| f.timestamp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{suspect, trade};
+
+    const T0: i64 = 1_700_000_000;
+
+    fn buy(size: f64, timestamp: i64) -> SuspectTrade {
+        suspect(trade("0xwallet", "BUY", 0.2, size, timestamp), AlertLevel::High, Detector::FreshWallet)
+    }
+
+    #[test]
+    fn first_alert_is_admitted() {
+        let mut dedup = AlertDedup::default();
+        let (fresh, merged) = dedup.admit(vec![buy(50_000.0, T0)], T0);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(merged, 0);
+        assert!(dedup.take_updates(T0).is_empty());
+    }
+
+    #[test]
+    fn repeat_within_cooldown_is_merged_and_reported_as_update() {
+        let mut dedup = AlertDedup::default();
+        dedup.admit(vec![buy(50_000.0, T0)], T0);
+
+        let (fresh, merged) = dedup.admit(vec![buy(25_000.0, T0 + 60)], T0 + 60);
+        assert!(fresh.is_empty());
+        assert_eq!(merged, 1);
+
+        // Not before the update interval
+        assert!(dedup.take_updates(T0 + 60).is_empty());
+        let updates = dedup.take_updates(T0 + alert_update_interval_secs());
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].previous_value_usd, 10_000.0);
+        assert_eq!(updates[0].suspect.position_value_usd(), 15_000.0);
+        assert_eq!(updates[0].suspect.fills.len(), 2);
+        assert_eq!(updates[0].suspect.alert_id(), buy(50_000.0, T0).alert_id());
+
+        // Reported once
+        assert!(dedup.take_updates(T0 + 2 * alert_update_interval_secs()).is_empty());
+    }
+
+    #[test]
+    fn repeat_after_cooldown_is_a_new_alert() {
+        let mut dedup = AlertDedup::default();
+        dedup.admit(vec![buy(50_000.0, T0)], T0);
+
+        let later = T0 + alert_cooldown_secs() + 1;
+        let (fresh, merged) = dedup.admit(vec![buy(25_000.0, later)], later);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(merged, 0);
+    }
+
+    #[test]
+    fn other_outcome_of_same_market_stays_separate() {
+        let mut dedup = AlertDedup::default();
+        dedup.admit(vec![buy(50_000.0, T0)], T0);
+
+        let mut no = buy(25_000.0, T0 + 60);
+        no.trade.outcome = Some("No".to_string());
+        no.trade.outcome_index = Some(1);
+        let (fresh, merged) = dedup.admit(vec![no], T0 + 60);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(merged, 0);
+    }
+}
@@ -0,0 +1,426 @@
+//! Per-trade detection decisions
+//!
+//! What turns a trade (or a group of fills) into an alert, and how the alert
+//! is scored, given the wallet's stats, the market and the bet's context.
+//! Nothing here does I/O: the tracker feeds these functions from live API
+//! lookups and `backtest` from the recorded trade history, so both apply
+//! exactly the same rules.
+
+use crate::allowlist::{AllowAction, Allowlist};
+use crate::anomaly::Anomaly;
+use crate::api::mask_address;
+use crate::chain::FundingSourceKind;
+use crate::cluster::cluster_wallets;
+use crate::config::{
+    anomaly_bucket_secs, min_trade_size_usd, pre_resolution_only, pre_resolution_window_hours,
+    record_min_longshot_hit_rate, record_min_longshots, Thresholds,
+};
+use crate::outcomes::BetContext;
+use crate::scoring::{
+    contrarian_points, freshness_points, funding_points, record_points, size_points, timing_points, SuspicionScore,
+};
+use crate::types::{AlertLevel, Detector, Market, SuspectTrade, TrackRecord, Trade, UserStats};
+
+// ============================================================================
+// WALLET SIGNALS
+// ============================================================================
+
+/// What makes a wallet worth alerting on, before looking at its trade
+pub enum WalletSignal {
+    /// Few markets and young; carries the age description
+    Fresh(String),
+    /// Established but low-volume, with an implausibly good long-shot record
+    LongShotRecord,
+}
+
+/// CRITERIA 1 + 2: a fresh wallet (few markets, young), or an established
+/// low-volume wallet with an implausibly good record on long shots
+pub fn wallet_signal(user_stats: &UserStats, thresholds: &Thresholds, now_ts: i64) -> Option<WalletSignal> {
+    if let Some(age) = user_stats.fresh_age(thresholds, now_ts) {
+        return Some(WalletSignal::Fresh(age));
+    }
+    user_stats
+        .record
+        .as_ref()
+        .filter(|r| is_sharp_longshot_record(r))
+        .map(|_| WalletSignal::LongShotRecord)
+}
+
+pub fn is_sharp_longshot_record(record: &TrackRecord) -> bool {
+    record.longshot_bets >= record_min_longshots()
+        && record
+            .longshot_hit_rate()
+            .is_some_and(|rate| rate >= record_min_longshot_hit_rate())
+}
+
+// ============================================================================
+// DETECTORS
+// ============================================================================
+
+/// A large aggressive trade that already passed the size, taker and odds
+/// filters: alert when the wallet has a signal and the bet is contrarian in
+/// its event's context
+pub fn evaluate_trade(
+    trade: Trade,
+    user_stats: UserStats,
+    thresholds: &Thresholds,
+    context: Option<&BetContext>,
+    now_ts: i64,
+) -> Option<SuspectTrade> {
+    let signal = wallet_signal(&user_stats, thresholds, now_ts)?;
+
+    // CRITERIA 3: Contrarian in event context (not just a low raw price),
+    // falling back to the raw price when the market is unknown
+    let contrarian = match context {
+        Some(context) => context.is_contrarian(thresholds.max_price),
+        None => trade.effective_price() < thresholds.max_price,
+    };
+    if !contrarian {
+        return None;
+    }
+
+    let value_usd = trade.value_usd();
+    let is_fresh = matches!(signal, WalletSignal::Fresh(_));
+
+    let mut reasons = vec![match signal {
+        WalletSignal::Fresh(age_info) => format!("Fresh Wallet ({} mkts, {})", user_stats.unique_markets, age_info),
+        WalletSignal::LongShotRecord => format!(
+            "Long-shot Record ({} mkts, {})",
+            user_stats.unique_markets,
+            user_stats.record.as_ref().map(|r| r.summary()).unwrap_or_default()
+        ),
+    }];
+
+    let is_sell = trade.is_taker_sell();
+    if is_sell {
+        reasons.push(format!(
+            "Taker SELL of favourite at {:.1}% (contrarian)",
+            trade.price * 100.0
+        ));
+    } else {
+        reasons.push("Taker BUY (aggressive)".to_string());
+    }
+    // Established wallets keep their own label whichever side they trade
+    let detector = match (is_fresh, is_sell) {
+        (true, true) => Detector::ContrarianSell,
+        (true, false) => Detector::FreshWallet,
+        (false, _) => Detector::LongShotRecord,
+    };
+
+    let alert_level = if value_usd >= thresholds.min_trade_size_usd {
+        reasons.push(format!("Large Position (${:.0})", value_usd));
+        AlertLevel::High
+    } else {
+        // Keep medium for smaller tests if any leak through
+        AlertLevel::Medium
+    };
+
+    Some(suspect(trade, user_stats, reasons.join(" | "), alert_level, detector, Vec::new()))
+}
+
+/// An aggregated split-order position that crossed the size threshold
+pub fn evaluate_split_order(
+    fills: Vec<Trade>,
+    user_stats: UserStats,
+    thresholds: &Thresholds,
+    now_ts: i64,
+) -> Option<SuspectTrade> {
+    let trade = fills.last()?.clone();
+    let total_usd: f64 = fills.iter().map(|f| f.value_usd()).sum();
+    let span_mins = (trade.timestamp - fills[0].timestamp) / 60;
+
+    let mut reasons = vec![format!(
+        "Split Order ({} fills, ${:.0} in {}m)",
+        fills.len(),
+        total_usd,
+        span_mins
+    )];
+
+    // Splitting alone is a weak signal; a fresh wallet splitting is the real thing
+    let alert_level = match user_stats.fresh_age(thresholds, now_ts) {
+        Some(age_info) => {
+            reasons.push(format!(
+                "Fresh Wallet ({} mkts, {})",
+                user_stats.unique_markets,
+                age_info
+            ));
+            AlertLevel::High
+        }
+        None => AlertLevel::Low,
+    };
+    reasons.push("Taker BUY (aggressive)".to_string());
+
+    Some(suspect(trade, user_stats, reasons.join(" | "), alert_level, Detector::SplitOrder, fills))
+}
+
+/// A single alert covering every wallet in a cluster. `user_stats` is the
+/// wallet that completed it, `members` every wallet in it.
+pub fn cluster_suspect(trades: Vec<Trade>, user_stats: UserStats, members: Vec<UserStats>) -> SuspectTrade {
+    let trade = trades[trades.len() - 1].clone();
+    let wallets = cluster_wallets(&trades);
+    let total_usd: f64 = trades.iter().map(|t| t.value_usd()).sum();
+    let span_mins = (trade.timestamp - trades[0].timestamp) / 60;
+
+    let masked: Vec<String> = wallets.iter().map(|w| mask_address(w)).collect();
+    let reason = [
+        format!(
+            "Cluster ({} fresh wallets, ${:.0} in {}m)",
+            wallets.len(),
+            total_usd,
+            span_mins
+        ),
+        format!("Wallets: {}", masked.join(", ")),
+        "Taker BUY (aggressive)".to_string(),
+    ]
+    .join(" | ");
+
+    SuspectTrade {
+        members,
+        ..suspect(trade, user_stats, reason, AlertLevel::High, Detector::Cluster, trades)
+    }
+}
+
+/// A sell by a wallet that was flagged earlier
+pub fn exit_suspect(trade: Trade, user_stats: UserStats, flagged_level: AlertLevel) -> SuspectTrade {
+    let reason = [
+        format!("Informed Exit (wallet flagged {} earlier)", flagged_level),
+        format!("Taker SELL ${:.0} at {:.1}%", trade.value_usd(), trade.price * 100.0),
+    ]
+    .join(" | ");
+
+    // An exit is at least as interesting as the entry that flagged the wallet
+    let alert_level = match flagged_level {
+        AlertLevel::High => AlertLevel::High,
+        _ => AlertLevel::Medium,
+    };
+
+    suspect(trade, user_stats, reason, alert_level, Detector::InformedExit, Vec::new())
+}
+
+/// Any trade by a watched wallet: Low, Medium once it's position-sized
+pub fn watched_suspect(trade: Trade, user_stats: UserStats) -> SuspectTrade {
+    let value_usd = trade.value_usd();
+    let reason = [
+        "Watched Wallet Activity".to_string(),
+        format!("Taker {} ${:.0} at {:.1}%", trade.side.to_uppercase(), value_usd, trade.price * 100.0),
+    ]
+    .join(" | ");
+
+    let alert_level = if value_usd >= min_trade_size_usd() {
+        AlertLevel::Medium
+    } else {
+        AlertLevel::Low
+    };
+
+    suspect(trade, user_stats, reason, alert_level, Detector::WatchedWallet, Vec::new())
+}
+
+/// A fresh wallet trading in a watched market, at any size or odds
+pub fn evaluate_watched_market(
+    trade: Trade,
+    user_stats: UserStats,
+    thresholds: &Thresholds,
+    now_ts: i64,
+) -> Option<SuspectTrade> {
+    let age_info = user_stats.fresh_age(thresholds, now_ts)?;
+
+    let value_usd = trade.value_usd();
+    let reason = [
+        format!("Fresh Wallet ({} mkts, {})", user_stats.unique_markets, age_info),
+        format!("Taker {} ${:.0} at {:.1}%", trade.side.to_uppercase(), value_usd, trade.price * 100.0),
+    ]
+    .join(" | ");
+
+    let alert_level = if value_usd >= thresholds.min_trade_size_usd {
+        AlertLevel::Medium
+    } else {
+        AlertLevel::Low
+    };
+
+    Some(suspect(trade, user_stats, reason, alert_level, Detector::WatchedMarket, Vec::new()))
+}
+
+/// The trade a market anomaly is attributed to: its largest taker buy
+pub fn anomaly_trade(anomaly: &Anomaly) -> Option<Trade> {
+    let trades = match anomaly {
+        Anomaly::VolumeSpike { trades, .. } | Anomaly::PriceDrift { trades, .. } => trades,
+    };
+    trades
+        .iter()
+        .filter(|t| t.is_taker_buy())
+        .max_by(|a, b| a.value_usd().total_cmp(&b.value_usd()))
+        .or_else(|| trades.last())
+        .cloned()
+}
+
+/// A market anomaly as an alert on `trade` (see `anomaly_trade`)
+pub fn anomaly_suspect(anomaly: Anomaly, trade: Trade, user_stats: UserStats) -> SuspectTrade {
+    let (detector, reason, trades) = match anomaly {
+        Anomaly::VolumeSpike { volume_usd, baseline_usd, zscore, trades } => (
+            Detector::VolumeSpike,
+            format!(
+                "Volume Spike (${:.0} taker buys in {}m vs ${:.0} baseline, z={:.1})",
+                volume_usd,
+                anomaly_bucket_secs() / 60,
+                baseline_usd,
+                zscore
+            ),
+            trades,
+        ),
+        Anomaly::PriceDrift { from_price, to_price, span_secs, trades } => (
+            Detector::PriceDrift,
+            format!(
+                "Price Drift ({:.1}% → {:.1}% over {})",
+                from_price * 100.0,
+                to_price * 100.0,
+                format_hours(span_secs as f64 / 3600.0)
+            ),
+            trades,
+        ),
+    };
+
+    suspect(trade, user_stats, reason, AlertLevel::Medium, detector, trades)
+}
+
+/// Whether a trade is already part of a wallet-level alert
+pub fn covered_by_wallet_alert(suspects: &[SuspectTrade], trade: &Trade) -> bool {
+    let id = trade.unique_id();
+    suspects.iter().any(|s| {
+        s.detector.is_wallet_level()
+            && (s.trade.unique_id() == id || s.fills.iter().any(|f| f.unique_id() == id))
+    })
+}
+
+fn suspect(
+    trade: Trade,
+    user_stats: UserStats,
+    reason: String,
+    alert_level: AlertLevel,
+    detector: Detector,
+    fills: Vec<Trade>,
+) -> SuspectTrade {
+    SuspectTrade {
+        trade,
+        user_stats,
+        reason,
+        alert_level,
+        detector,
+        fills,
+        members: Vec::new(),
+        market: None,
+        bet: None,
+        score: SuspicionScore::default(),
+    }
+}
+
+// ============================================================================
+// ALLOWLIST
+// ============================================================================
+
+/// Allowlist entries that drop every alert on a wallet
+pub fn is_suppressed(allowlist: &Allowlist, trade: &Trade) -> bool {
+    allowlist
+        .lookup(trade)
+        .is_some_and(|entry| matches!(entry.action, AllowAction::Suppress))
+}
+
+/// Keep an allowlisted wallet's alert, but at LOW
+pub fn cap_low(suspect: &mut SuspectTrade, name: &str) {
+    suspect.alert_level = AlertLevel::Low;
+    suspect.reason = format!("{} | Allowlisted: {} (capped LOW)", suspect.reason, name);
+}
+
+// ============================================================================
+// SCORING
+// ============================================================================
+
+/// Attach market metadata and the suspicion score. Returns None when the
+/// trade falls outside the pre-resolution window and PRE_RESOLUTION_ONLY is set.
+pub fn score_suspect(
+    mut suspect: SuspectTrade,
+    market: Option<Market>,
+    context: Option<BetContext>,
+    thresholds: &Thresholds,
+    watched_market: bool,
+    now_ts: i64,
+) -> Option<SuspectTrade> {
+    let window_hours = pre_resolution_window_hours();
+    let hours_to_resolution = market
+        .as_ref()
+        .and_then(|m| m.end_timestamp())
+        .map(|end| (end - suspect.trade.timestamp) as f64 / 3600.0);
+    let in_window = hours_to_resolution
+        .map(|h| h <= window_hours)
+        .unwrap_or(false);
+
+    // Watched wallets bypass every filter, the pre-resolution one included
+    if pre_resolution_only() && !in_window && suspect.detector != Detector::WatchedWallet {
+        return None;
+    }
+
+    // A cluster is as fresh as all its members, and as suspicious as its
+    // most suspiciously funded one
+    let wallets = suspect.wallet_stats();
+    let fresh = wallets.iter().all(|s| s.fresh_age(thresholds, now_ts).is_some());
+    let funding = wallets
+        .iter()
+        .filter_map(|s| s.funding.clone())
+        .map(|f| {
+            let lead_hours = (suspect.trade.timestamp - f.transfer.timestamp) as f64 / 3600.0;
+            (funding_points(&f.source_kind, lead_hours), f)
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut score = SuspicionScore::default();
+    score.add("fresh", freshness_points(fresh));
+    score.add("size", size_points(suspect.position_value_usd(), thresholds.min_trade_size_usd));
+    if let Some(record) = &suspect.user_stats.record {
+        score.add("record", record_points(record, record_min_longshots()));
+    }
+    let implied_prob = context
+        .as_ref()
+        .map(|c| c.implied_prob)
+        .unwrap_or_else(|| suspect.trade.effective_price());
+    score.add("odds", contrarian_points(implied_prob, thresholds.max_price));
+
+    if let Some((points, funding)) = funding {
+        score.add("funding", points);
+        if funding.source_kind != FundingSourceKind::Unknown {
+            suspect.reason = format!(
+                "{} | Funded: {}",
+                suspect.reason,
+                funding.describe(suspect.trade.timestamp)
+            );
+        }
+    }
+
+    if let (Some(hours), true) = (hours_to_resolution, in_window) {
+        score.add("timing", timing_points(hours, window_hours));
+        let timing = if hours <= 0.0 {
+            "placed after scheduled end, awaiting resolution".to_string()
+        } else {
+            format!("placed {} before scheduled resolution", format_hours(hours))
+        };
+        suspect.reason = format!("{} | {}", suspect.reason, timing);
+    }
+
+    if watched_market {
+        suspect.reason = format!("{} | Watched market", suspect.reason);
+    }
+
+    suspect.market = market;
+    suspect.bet = context.map(|c| c.description);
+    suspect.score = score;
+    Some(suspect)
+}
+
+/// Human-friendly duration ("45m", "3h", "2d")
+pub fn format_hours(hours: f64) -> String {
+    if hours < 1.0 {
+        format!("{:.0}m", hours * 60.0)
+    } else if hours < 48.0 {
+        format!("{:.0}h", hours)
+    } else {
+        format!("{:.0}d", hours / 24.0)
+    }
+}
@@ -0,0 +1,337 @@
+//! Periodic digest summaries
+//!
+//! Besides real-time alerts, the tracker can send a digest every hour, day or
+//! DIGEST_INTERVAL seconds: trades processed and the filter funnel of the
+//! `[POLL #n]` lines summed over the period, every alert ranked by score, the
+//! markets with the most suspicious volume, and alerts whose markets resolved.
+//! Periods line up with the clock (hourly digests cover 04:00 to 05:00 UTC).
+//! Funnel counts are kept in `DATA_DIR/digest_state.json` (saved every few
+//! polls, on rollover and on shutdown), so a restart doesn't reset the period.
+//!
+//! Webhooks get the digest as JSON. Like the alert payload, its field names
+//! are a contract: add fields freely, but rename or remove one only together
+//! with a bump of `SCHEMA_VERSION`.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::load_alerts;
+use crate::config::digest_interval;
+use crate::followup::load_outcomes;
+use crate::storage::{load_json, save_json};
+use crate::types::{AlertLevel, Detector};
+
+const DIGEST_STATE_FILE: &str = "digest_state.json";
+
+pub const SCHEMA: &str = "polymarket-insider-tracker/digest";
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Markets listed under "top markets"
+const TOP_MARKETS: usize = 5;
+
+/// Polls between saves of the running counts (periods also save on rollover
+/// and on shutdown)
+const SAVE_EVERY_POLLS: u64 = 30;
+
+/// Filter funnel counts, summed over the polls of a period
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FunnelStats {
+    pub polls: u64,
+    /// New trades processed
+    pub trades: usize,
+    pub non_gambling: usize,
+    pub large: usize,
+    pub contrarian: usize,
+    pub watched: usize,
+    /// Repeat alerts merged into an open one
+    pub repeats: usize,
+    pub alerts: usize,
+}
+
+impl FunnelStats {
+    fn add(&mut self, poll: &FunnelStats) {
+        self.polls += poll.polls;
+        self.trades += poll.trades;
+        self.non_gambling += poll.non_gambling;
+        self.large += poll.large;
+        self.contrarian += poll.contrarian;
+        self.watched += poll.watched;
+        self.repeats += poll.repeats;
+        self.alerts += poll.alerts;
+    }
+}
+
+// ============================================================================
+// DIGEST
+// ============================================================================
+
+/// One digest, as sent to the sinks (and as JSON to webhooks)
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub schema: &'static str,
+    pub schema_version: u32,
+    /// "digest", to tell it apart from alerts in a webhook
+    pub event: &'static str,
+    pub period_start: i64,
+    pub period_end: i64,
+    /// e.g. "2025-01-03 04:00 to 05:00 UTC"
+    pub period_label: String,
+    pub funnel: FunnelStats,
+    /// Alerts of the period, highest score first
+    pub alerts: Vec<DigestAlert>,
+    /// Markets by total alerted value, highest first
+    pub top_markets: Vec<MarketVolume>,
+    /// Alerts whose market resolved during the period
+    pub resolved: Vec<ResolvedAlert>,
+    pub correct: usize,
+    pub incorrect: usize,
+    /// Combined PnL of the resolved alerts' positions
+    pub resolved_pnl_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestAlert {
+    pub id: String,
+    pub created_at: i64,
+    pub level: AlertLevel,
+    pub detector: Detector,
+    pub score: f64,
+    pub wallet: String,
+    pub market_title: String,
+    pub outcome: Option<String>,
+    pub value_usd: f64,
+    pub price: f64,
+    pub market_url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketVolume {
+    pub title: String,
+    pub url: String,
+    pub alerts: usize,
+    pub value_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedAlert {
+    pub id: String,
+    pub level: AlertLevel,
+    pub market_title: String,
+    pub outcome: Option<String>,
+    pub won: bool,
+    pub winner: Option<String>,
+    pub pnl_usd: f64,
+}
+
+impl Digest {
+    /// Build the digest of a period from the alert history and follow-ups
+    pub fn build(period_start: i64, period_end: i64, funnel: FunnelStats) -> Result<Self> {
+        let alerts = load_alerts()?;
+        let outcomes = load_outcomes()?;
+
+        let mut ranked: Vec<DigestAlert> = alerts
+            .iter()
+            .filter(|a| a.created_at >= period_start && a.created_at < period_end)
+            .map(|a| DigestAlert {
+                id: a.id.clone(),
+                created_at: a.created_at,
+                level: a.alert_level,
+                detector: a.detector,
+                score: a.score,
+                wallet: a.wallet.clone(),
+                market_title: market_title(&a.market_title),
+                outcome: a.outcome.clone(),
+                value_usd: a.value_usd,
+                price: a.price,
+                market_url: a.market_url.clone(),
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut markets: HashMap<String, MarketVolume> = HashMap::new();
+        for alert in &ranked {
+            let market = markets.entry(alert.market_url.clone()).or_insert_with(|| MarketVolume {
+                title: alert.market_title.clone(),
+                url: alert.market_url.clone(),
+                alerts: 0,
+                value_usd: 0.0,
+            });
+            market.alerts += 1;
+            market.value_usd += alert.value_usd;
+        }
+        let mut top_markets: Vec<MarketVolume> = markets.into_values().collect();
+        top_markets.sort_by(|a, b| b.value_usd.total_cmp(&a.value_usd));
+        top_markets.truncate(TOP_MARKETS);
+
+        let resolved: Vec<ResolvedAlert> = alerts
+            .iter()
+            .filter_map(|alert| {
+                let outcome = outcomes.get(&alert.id)?;
+                let resolved_at = outcome.resolved_at?;
+                if resolved_at < period_start || resolved_at >= period_end {
+                    return None;
+                }
+                Some(ResolvedAlert {
+                    id: alert.id.clone(),
+                    level: alert.alert_level,
+                    market_title: market_title(&alert.market_title),
+                    outcome: alert.outcome.clone(),
+                    won: outcome.won?,
+                    winner: outcome.winner.clone(),
+                    pnl_usd: outcome.pnl_usd.unwrap_or_default(),
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            schema: SCHEMA,
+            schema_version: SCHEMA_VERSION,
+            event: "digest",
+            period_start,
+            period_end,
+            period_label: period_label(period_start, period_end),
+            funnel,
+            alerts: ranked,
+            top_markets,
+            correct: resolved.iter().filter(|r| r.won).count(),
+            incorrect: resolved.iter().filter(|r| !r.won).count(),
+            resolved_pnl_usd: resolved.iter().fold(0.0, |sum, r| sum + r.pnl_usd),
+            resolved,
+        })
+    }
+}
+
+fn market_title(title: &Option<String>) -> String {
+    title.clone().unwrap_or_else(|| "Unknown Market".to_string())
+}
+
+/// "2025-01-03 04:00 to 05:00 UTC", with the end date when it differs
+fn period_label(start: i64, end: i64) -> String {
+    let (Some(start), Some(end)) = (
+        chrono::DateTime::from_timestamp(start, 0),
+        chrono::DateTime::from_timestamp(end, 0),
+    ) else {
+        return format!("{} to {}", start, end);
+    };
+    let end_format = if start.date_naive() == end.date_naive() { "%H:%M" } else { "%Y-%m-%d %H:%M" };
+    format!("{} to {} UTC", start.format("%Y-%m-%d %H:%M"), end.format(end_format))
+}
+
+// ============================================================================
+// SCHEDULE
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct DigestState {
+    period_start: i64,
+    funnel: FunnelStats,
+}
+
+/// Collects funnel counts and says when a period's digest is due
+pub struct DigestSchedule {
+    interval_secs: i64,
+    state: DigestState,
+    /// Polls recorded since the last save
+    unsaved_polls: u64,
+}
+
+impl DigestSchedule {
+    /// The configured schedule, resuming the current period; None when disabled
+    pub fn from_config() -> Result<Option<Self>> {
+        let Some(interval) = digest_interval() else {
+            return Ok(None);
+        };
+        let interval_secs = parse_interval(&interval)?;
+
+        let now = chrono::Utc::now().timestamp();
+        let state = match load_json::<DigestState>(DIGEST_STATE_FILE) {
+            Ok(Some(state)) => state,
+            Ok(None) => DigestState::default(),
+            Err(e) => {
+                eprintln!("{} Digest state not loaded, starting a new period: {:#}", "⚠️".yellow(), e);
+                DigestState::default()
+            }
+        };
+        // A period left over from an earlier run is still reported once;
+        // without one, start counting now
+        let state = if state.period_start > 0 {
+            state
+        } else {
+            DigestState {
+                period_start: period_start(now, interval_secs),
+                funnel: FunnelStats::default(),
+            }
+        };
+        Ok(Some(Self {
+            interval_secs,
+            state,
+            unsaved_polls: 0,
+        }))
+    }
+
+    /// e.g. "hourly", "daily" or "every 900s"
+    pub fn describe(&self) -> String {
+        match self.interval_secs {
+            3600 => "hourly".to_string(),
+            86400 => "daily".to_string(),
+            secs => format!("every {}s", secs),
+        }
+    }
+
+    /// Add one poll's funnel counts to the current period
+    pub fn record_poll(&mut self, poll: &FunnelStats) {
+        self.state.funnel.add(poll);
+        self.unsaved_polls += 1;
+        if self.unsaved_polls >= SAVE_EVERY_POLLS {
+            self.save();
+        }
+    }
+
+    /// Save counts not yet persisted, before exiting
+    pub fn flush(&mut self) {
+        if self.unsaved_polls > 0 {
+            self.save();
+        }
+    }
+
+    /// Once the current period is over: its bounds and counts. The next
+    /// period starts at the current clock-aligned boundary.
+    pub fn take_due(&mut self, now_ts: i64) -> Option<(i64, i64, FunnelStats)> {
+        let end = self.state.period_start + self.interval_secs;
+        if now_ts < end {
+            return None;
+        }
+        let start = self.state.period_start;
+        let funnel = std::mem::take(&mut self.state.funnel);
+        self.state.period_start = period_start(now_ts, self.interval_secs);
+        self.save();
+        Some((start, end, funnel))
+    }
+
+    fn save(&mut self) {
+        match save_json(DIGEST_STATE_FILE, &self.state) {
+            Ok(()) => self.unsaved_polls = 0,
+            Err(e) => eprintln!("{} Failed to save digest state: {:#}", "❌".red(), e),
+        }
+    }
+}
+
+/// Start of the clock-aligned period containing `ts`
+fn period_start(ts: i64, interval_secs: i64) -> i64 {
+    ts - ts.rem_euclid(interval_secs)
+}
+
+fn parse_interval(value: &str) -> Result<i64> {
+    let secs = match value.trim().to_lowercase().as_str() {
+        "hourly" => 3600,
+        "daily" => 86400,
+        other => match other.parse::<i64>() {
+            Ok(secs) if secs >= 60 => secs,
+            _ => bail!("Invalid DIGEST_INTERVAL '{}' (hourly, daily or seconds >= 60)", value),
+        },
+    };
+    Ok(secs)
+}
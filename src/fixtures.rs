@@ -0,0 +1,57 @@
+//! Sample trades and alerts shared by unit tests
+
+use crate::scoring::SuspicionScore;
+use crate::types::{AlertLevel, Detector, SuspectTrade, Trade, UserStats};
+
+/// A trade on the "Yes" outcome of market "0xmarket"
+pub fn trade(wallet: &str, side: &str, price: f64, size: f64, timestamp: i64) -> Trade {
+    Trade {
+        proxy_wallet: wallet.to_string(),
+        side: side.to_string(),
+        asset: Some("yes-token".to_string()),
+        condition_id: Some("0xmarket".to_string()),
+        size,
+        price,
+        timestamp,
+        title: Some("Will it happen?".to_string()),
+        slug: Some("will-it-happen".to_string()),
+        icon: None,
+        event_slug: Some("will-it-happen".to_string()),
+        outcome: Some("Yes".to_string()),
+        outcome_index: Some(0),
+        name: None,
+        pseudonym: None,
+        bio: None,
+        profile_image: None,
+        profile_image_optimized: None,
+        transaction_hash: None,
+    }
+}
+
+/// A wallet with a single prior market and no known age
+pub fn user_stats(wallet: &str) -> UserStats {
+    UserStats {
+        address: wallet.to_string(),
+        unique_markets: 1,
+        total_trades: 1,
+        first_activity_timestamp: None,
+        funding: None,
+        record: None,
+    }
+}
+
+/// A single-trade alert on `trade`
+pub fn suspect(trade: Trade, alert_level: AlertLevel, detector: Detector) -> SuspectTrade {
+    SuspectTrade {
+        user_stats: user_stats(&trade.proxy_wallet),
+        trade,
+        reason: "Fresh Wallet (1 mkts, New)".to_string(),
+        alert_level,
+        detector,
+        fills: Vec::new(),
+        members: Vec::new(),
+        market: None,
+        bet: None,
+        score: SuspicionScore::default(),
+    }
+}
@@ -0,0 +1,218 @@
+//! Alert follow-up: did the flagged bet turn out right?
+//!
+//! Every persisted alert is revisited until it is complete: the outcome
+//! token's price is sampled at fixed horizons after the alert, and once the
+//! market resolves we record whether the bet won and what the alerted
+//! position made. Results live in `DATA_DIR/alert_outcomes.json`, keyed by
+//! alert ID.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::{load_alerts, AlertRecord};
+use crate::api::ApiClient;
+use crate::storage::{load_json, save_json};
+use crate::types::Market;
+
+const OUTCOMES_FILE: &str = "alert_outcomes.json";
+
+/// Hours after the alert at which the outcome price is sampled
+pub const PRICE_HORIZONS_HOURS: [i64; 3] = [1, 6, 24];
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AlertOutcome {
+    pub checked_at: i64,
+    pub category: Option<String>,
+    /// The market resolved and its winner is known
+    pub resolved: bool,
+    /// When the resolution was first seen
+    #[serde(default)]
+    pub resolved_at: Option<i64>,
+    /// Whether the alerted bet won (None until resolved)
+    pub won: Option<bool>,
+    /// Label of the winning outcome
+    #[serde(default)]
+    pub winner: Option<String>,
+    /// PnL of the alerted position at resolution
+    pub pnl_usd: Option<f64>,
+    /// PnL as a fraction of the stake
+    pub return_pct: Option<f64>,
+    /// Outcome token price N hours after the alert, keyed by N
+    #[serde(default)]
+    pub price_after: BTreeMap<i64, f64>,
+}
+
+impl AlertOutcome {
+    fn is_complete(&self) -> bool {
+        self.resolved && PRICE_HORIZONS_HOURS.iter().all(|h| self.price_after.contains_key(h))
+    }
+}
+
+/// Probability of the bet the alert is about: a SELL bets against the outcome
+pub fn bet_probability(alert: &AlertRecord, outcome_price: f64) -> f64 {
+    if alert.side.eq_ignore_ascii_case("SELL") {
+        1.0 - outcome_price
+    } else {
+        outcome_price
+    }
+}
+
+/// Move in the bet's favour `hours` after the alert, in probability points
+pub fn price_move(alert: &AlertRecord, outcome: &AlertOutcome, hours: i64) -> Option<f64> {
+    let after = outcome.price_after.get(&hours)?;
+    Some(bet_probability(alert, *after) - bet_probability(alert, alert.price))
+}
+
+/// PnL and return of the alerted position, given whether the bet won.
+/// A SELL of `size` shares at `p` stakes `size * (1 - p)` against the outcome.
+pub fn position_result(alert: &AlertRecord, won: bool) -> (f64, f64) {
+    let entry = bet_probability(alert, alert.price).clamp(0.001, 0.999);
+    let stake = if alert.side.eq_ignore_ascii_case("SELL") {
+        alert.value_usd * entry / (1.0 - entry)
+    } else {
+        alert.value_usd
+    };
+    let return_pct = if won { (1.0 - entry) / entry } else { -1.0 };
+    (stake * return_pct, return_pct)
+}
+
+/// Every recorded follow-up, by alert ID
+pub fn load_outcomes() -> Result<HashMap<String, AlertOutcome>> {
+    Ok(load_json(OUTCOMES_FILE)?.unwrap_or_default())
+}
+
+/// Counts from one follow-up pass
+#[derive(Debug, Default)]
+pub struct FollowupSummary {
+    pub checked: usize,
+    pub newly_resolved: usize,
+}
+
+/// Follow up alerts that aren't complete yet, least recently checked first
+/// and at most `max_alerts` of them, and persist the results
+pub async fn update_outcomes(client: &mut ApiClient, max_alerts: usize) -> Result<FollowupSummary> {
+    let alerts = load_alerts()?;
+    let mut outcomes = load_outcomes()?;
+    let now = chrono::Utc::now().timestamp();
+
+    let mut pending: Vec<&AlertRecord> = alerts
+        .iter()
+        .filter(|a| outcomes.get(&a.id).is_none_or(|o| !o.is_complete()))
+        .collect();
+    pending.sort_by_key(|a| outcomes.get(&a.id).map_or(0, |o| o.checked_at));
+    pending.truncate(max_alerts);
+
+    let condition_ids: Vec<String> = pending
+        .iter()
+        .filter_map(|a| a.condition_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut markets: HashMap<String, Market> = HashMap::new();
+    for chunk in condition_ids.chunks(20) {
+        for market in client.fetch_markets(chunk).await.unwrap_or_default() {
+            if let Some(cid) = market.condition_id.clone() {
+                markets.insert(cid, market);
+            }
+        }
+    }
+
+    sample_prices(client, &pending, &mut outcomes, now).await;
+
+    let mut summary = FollowupSummary::default();
+    for alert in pending {
+        let outcome = outcomes.entry(alert.id.clone()).or_default();
+        let market = alert.condition_id.as_ref().and_then(|cid| markets.get(cid));
+
+        if let Some(market) = market {
+            if outcome.category.is_none() {
+                outcome.category = market.category.clone();
+            }
+            if !outcome.resolved {
+                if let (Some(winner), Some(index)) = (market.winning_outcome_index(), alert_outcome_index(alert, market)) {
+                    let won = (winner == index) != alert.side.eq_ignore_ascii_case("SELL");
+                    let (pnl_usd, return_pct) = position_result(alert, won);
+                    outcome.resolved = true;
+                    outcome.resolved_at = Some(now);
+                    outcome.won = Some(won);
+                    outcome.winner = market.outcome_labels().get(winner).cloned();
+                    outcome.pnl_usd = Some(pnl_usd);
+                    outcome.return_pct = Some(return_pct);
+                    summary.newly_resolved += 1;
+                }
+            }
+        }
+
+        outcome.checked_at = now;
+        summary.checked += 1;
+    }
+
+    save_json(OUTCOMES_FILE, &outcomes)?;
+    Ok(summary)
+}
+
+/// Horizons that have passed but have no price yet
+fn due_horizons(alert: &AlertRecord, outcome: Option<&AlertOutcome>, now: i64) -> Vec<i64> {
+    PRICE_HORIZONS_HOURS
+        .iter()
+        .copied()
+        .filter(|h| outcome.is_none_or(|o| !o.price_after.contains_key(h)) && alert.trade_timestamp + h * 3600 <= now)
+        .collect()
+}
+
+/// Record the outcome price at each horizon that has passed. One
+/// price-history request per outcome token covers every alert on it.
+async fn sample_prices(
+    client: &mut ApiClient,
+    alerts: &[&AlertRecord],
+    outcomes: &mut HashMap<String, AlertOutcome>,
+    now: i64,
+) {
+    let mut by_asset: HashMap<&str, Vec<&AlertRecord>> = HashMap::new();
+    for alert in alerts {
+        if let Some(asset) = alert.asset.as_deref() {
+            if !due_horizons(alert, outcomes.get(&alert.id), now).is_empty() {
+                by_asset.entry(asset).or_default().push(alert);
+            }
+        }
+    }
+
+    for (asset, asset_alerts) in by_asset {
+        let start = asset_alerts.iter().map(|a| a.trade_timestamp).min().unwrap_or(now);
+        let end = asset_alerts
+            .iter()
+            .filter_map(|a| {
+                let last = due_horizons(a, outcomes.get(&a.id), now).into_iter().max()?;
+                Some(a.trade_timestamp + last * 3600 + 3600)
+            })
+            .max()
+            .unwrap_or(now);
+        let Ok(history) = client.fetch_price_history(asset, start, end).await else {
+            continue;
+        };
+
+        for alert in asset_alerts {
+            let outcome = outcomes.entry(alert.id.clone()).or_default();
+            for hours in due_horizons(alert, Some(outcome), now) {
+                let target = alert.trade_timestamp + hours * 3600;
+                if let Some(point) = history.iter().find(|p| p.t >= target) {
+                    outcome.price_after.insert(hours, point.p);
+                }
+            }
+        }
+    }
+}
+
+/// Outcome index of the alerted trade, falling back to matching its label
+pub fn alert_outcome_index(alert: &AlertRecord, market: &Market) -> Option<usize> {
+    if let Some(index) = alert.outcome_index {
+        return usize::try_from(index).ok();
+    }
+    let label = alert.outcome.as_deref()?;
+    market
+        .outcome_labels()
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(label))
+}
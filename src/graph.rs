@@ -0,0 +1,283 @@
+//! Wallet linkage graph
+//!
+//! "Fresh" wallets are often the same operator. Wallets are linked by a shared
+//! funding source, repeatedly trading the same outcome within seconds of each
+//! other, or opening the same sequence of market/outcome positions. The graph
+//! and the set of flagged wallets are persisted so links survive restarts.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::chain::{FundingSourceKind, WalletFunding};
+use crate::storage::{load_json, save_json};
+use crate::types::{AlertLevel, Trade};
+
+const GRAPH_FILE: &str = "wallet_graph.json";
+
+/// (lower wallet, higher wallet)
+type WalletPair = (String, String);
+/// (market, outcome)
+type OutcomeKey = (String, String);
+
+/// Distinct market/outcome positions compared for sequence links
+const SEQUENCE_LENGTH: usize = 3;
+
+/// A pair co-trading one outcome counts once per this span, however many
+/// fills each side's order took
+const CO_TRADE_EPISODE_SECS: i64 = 3600;
+
+/// Why two wallets are linked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkReason {
+    /// One wallet funded the other
+    FundedBy,
+    /// Both funded from the same (non-exchange) address
+    SharedFunding,
+    /// Repeatedly bought the same outcome within seconds of each other
+    CoTrading,
+    /// Opened the same sequence of market/outcome positions
+    SameSequence,
+}
+
+impl std::fmt::Display for LinkReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkReason::FundedBy => write!(f, "funding transfer"),
+            LinkReason::SharedFunding => write!(f, "shared funding"),
+            LinkReason::CoTrading => write!(f, "co-trading"),
+            LinkReason::SameSequence => write!(f, "same trade sequence"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WalletLink {
+    pub wallet: String,
+    pub reason: LinkReason,
+    pub since: i64,
+}
+
+/// Linking thresholds
+pub struct LinkParams {
+    /// Max seconds between two wallets' trades to count as co-trading
+    pub co_trade_secs: i64,
+    /// Co-trades needed before two wallets are linked
+    pub min_co_trades: usize,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct WalletGraph {
+    links: HashMap<String, Vec<WalletLink>>,
+    flagged: HashMap<String, AlertLevel>,
+    /// Funding source -> wallets it funded
+    funded_by_source: HashMap<String, Vec<String>>,
+
+    // Observation state, rebuilt from the live stream
+    #[serde(skip)]
+    recent_by_outcome: HashMap<(String, String), VecDeque<(String, i64)>>,
+    #[serde(skip)]
+    co_trades: HashMap<(String, String), usize>,
+    /// Last counted co-trade per (wallet pair, market/outcome)
+    #[serde(skip)]
+    co_trade_counted: HashMap<(WalletPair, OutcomeKey), i64>,
+    #[serde(skip)]
+    sequences: HashMap<String, Vec<(String, String)>>,
+    #[serde(skip)]
+    sequence_index: HashMap<Vec<(String, String)>, Vec<String>>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl WalletGraph {
+    /// Load the persisted graph, or start empty
+    pub fn load() -> Self {
+        match load_json::<WalletGraph>(GRAPH_FILE) {
+            Ok(Some(graph)) => graph,
+            Ok(None) => Self::default(),
+            Err(e) => {
+                eprintln!("⚠️  Wallet graph not loaded, starting empty: {:#}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Persist if anything changed since the last save
+    pub fn save_if_dirty(&mut self) -> Result<()> {
+        if self.dirty {
+            save_json(GRAPH_FILE, self)?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // FLAGS
+    // ------------------------------------------------------------------------
+
+    /// Highest alert level a wallet has been flagged at
+    pub fn flagged_level(&self, wallet: &str) -> Option<AlertLevel> {
+        self.flagged.get(&wallet.to_lowercase()).copied()
+    }
+
+    /// Record that a wallet produced an alert (keeps the highest level)
+    pub fn flag(&mut self, wallet: &str, level: AlertLevel) {
+        let entry = self.flagged.entry(wallet.to_lowercase()).or_insert(level);
+        if level == AlertLevel::High {
+            *entry = AlertLevel::High;
+        }
+        self.dirty = true;
+    }
+
+    // ------------------------------------------------------------------------
+    // LINKS
+    // ------------------------------------------------------------------------
+
+    /// Every wallet directly linked to `wallet`
+    pub fn links(&self, wallet: &str) -> &[WalletLink] {
+        self.links
+            .get(&wallet.to_lowercase())
+            .map(|l| l.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Linked wallets that were flagged before, with their level
+    pub fn linked_flagged(&self, wallet: &str) -> Vec<(&WalletLink, AlertLevel)> {
+        self.links(wallet)
+            .iter()
+            .filter_map(|link| Some((link, self.flagged_level(&link.wallet)?)))
+            .collect()
+    }
+
+    fn link(&mut self, a: &str, b: &str, reason: LinkReason, since: i64) {
+        let (a, b) = (a.to_lowercase(), b.to_lowercase());
+        if a == b {
+            return;
+        }
+        for (from, to) in [(&a, &b), (&b, &a)] {
+            let links = self.links.entry(from.clone()).or_default();
+            if !links.iter().any(|l| l.wallet == *to && l.reason == reason) {
+                links.push(WalletLink {
+                    wallet: to.clone(),
+                    reason,
+                    since,
+                });
+                self.dirty = true;
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // OBSERVATIONS
+    // ------------------------------------------------------------------------
+
+    /// Link a wallet to its funder and to other wallets with the same funder.
+    /// Exchanges fund everyone, so they never create links.
+    pub fn observe_funding(&mut self, wallet: &str, funding: &WalletFunding) {
+        if funding.source_kind == FundingSourceKind::Exchange {
+            return;
+        }
+        let wallet = wallet.to_lowercase();
+        let source = funding.transfer.from.to_lowercase();
+        let since = funding.transfer.timestamp;
+
+        self.link(&wallet, &source, LinkReason::FundedBy, since);
+
+        let siblings = self.funded_by_source.entry(source).or_default();
+        if siblings.contains(&wallet) {
+            return;
+        }
+        let others = siblings.clone();
+        siblings.push(wallet.clone());
+        for other in others {
+            self.link(&wallet, &other, LinkReason::SharedFunding, since);
+        }
+    }
+
+    /// Feed a batch from the live stream, oldest first, for co-trading and
+    /// sequence links
+    pub fn observe_trades(&mut self, trades: &[Trade], params: &LinkParams) {
+        let mut trades: Vec<&Trade> = trades.iter().collect();
+        trades.sort_by_key(|t| t.timestamp);
+        for trade in trades {
+            self.observe_trade(trade, params);
+        }
+    }
+
+    fn observe_trade(&mut self, trade: &Trade, params: &LinkParams) {
+        if !trade.is_taker_buy() {
+            return;
+        }
+        let Some(market) = trade.market_key() else {
+            return;
+        };
+        let wallet = trade.proxy_wallet.to_lowercase();
+        let key = (market, trade.outcome_key());
+
+        // Co-trading: other wallets on the same outcome within a few seconds
+        let recent = self.recent_by_outcome.entry(key.clone()).or_default();
+        recent.retain(|(_, ts)| (trade.timestamp - ts).abs() <= params.co_trade_secs);
+        let partners: HashSet<String> = recent
+            .iter()
+            .filter(|(w, _)| *w != wallet)
+            .map(|(w, _)| w.clone())
+            .collect();
+        recent.push_back((wallet.clone(), trade.timestamp));
+
+        for partner in partners {
+            let pair = if wallet < partner {
+                (wallet.clone(), partner.clone())
+            } else {
+                (partner.clone(), wallet.clone())
+            };
+            // Split fills of the same pair of orders are one co-trade
+            let counted = self.co_trade_counted.entry((pair.clone(), key.clone())).or_insert(i64::MIN);
+            if trade.timestamp.saturating_sub(*counted) < CO_TRADE_EPISODE_SECS {
+                continue;
+            }
+            *counted = trade.timestamp;
+
+            let count = self.co_trades.entry(pair).or_insert(0);
+            *count += 1;
+            if *count == params.min_co_trades {
+                self.link(&wallet, &partner, LinkReason::CoTrading, trade.timestamp);
+            }
+        }
+
+        // Sequences: the first few distinct positions a wallet opens
+        let sequence = self.sequences.entry(wallet.clone()).or_default();
+        if sequence.len() < SEQUENCE_LENGTH && !sequence.contains(&key) {
+            sequence.push(key);
+            if sequence.len() == SEQUENCE_LENGTH {
+                let sequence = sequence.clone();
+                let matches = self.sequence_index.entry(sequence).or_default();
+                let others = matches.clone();
+                matches.push(wallet.clone());
+                for other in others {
+                    self.link(&wallet, &other, LinkReason::SameSequence, trade.timestamp);
+                }
+            }
+        }
+
+        self.trim_observations();
+    }
+
+    /// Keep in-memory observation state bounded
+    fn trim_observations(&mut self) {
+        if self.recent_by_outcome.len() > 5000 {
+            self.recent_by_outcome.clear();
+        }
+        if self.co_trades.len() > 100_000 {
+            self.co_trades.retain(|_, count| *count > 1);
+        }
+        if self.co_trade_counted.len() > 100_000 {
+            self.co_trade_counted.clear();
+        }
+        if self.sequences.len() > 100_000 {
+            self.sequences.clear();
+            self.sequence_index.clear();
+        }
+    }
+}
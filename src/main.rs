@@ -5,10 +5,40 @@
 //!
 //! Usage:
 //!   cargo run --release
-
+//!   cargo run --release -- wallet <address> [--json]
+//!   cargo run --release -- watch add|remove <address> | watch list
+//!   cargo run --release -- watch-market add|remove <condition_id|event_slug> | watch-market list
+//!   cargo run --release -- report [--json] [--no-refresh]
+//!   cargo run --release -- backtest [--min-size 1000,5000] [--max-price 0.2,0.35] ...
+//!   cargo run --release -- dead-letters [replay]
+
+mod aggregator;
+mod alerts;
+mod allowlist;
+mod anomaly;
 mod api;
+mod backtest;
+mod chain;
+mod cli;
+mod cluster;
 mod config;
+mod dedup;
+mod detect;
+mod digest;
+#[cfg(test)]
+mod fixtures;
+mod followup;
+mod graph;
+mod market_watchlist;
+mod notify;
+mod outcomes;
+mod profile;
+mod record;
+mod report;
+mod scoring;
+mod storage;
 mod types;
+mod watchlist;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -18,35 +48,103 @@ use std::time::{Duration, Instant};
 use colored::*;
 use tokio::time::sleep;
 
+use aggregator::SplitOrderAggregator;
+use alerts::record_alert;
+use allowlist::{AllowAction, Allowlist, MarketMakerParams, MarketMakerStats};
+use anomaly::{Anomaly, AnomalyParams, MarketAnomalyDetector};
 use api::{mask_address, ApiClient};
+use cli::Command;
+use chain::{
+    classify_funding, load_address_labels, AddressLabel, ChainProvider, WalletFunding,
+};
+use cluster::{cluster_wallets, ClusterDetector, ClusterParams};
 use config::{
-    discord_webhook_url, max_unique_markets, min_trade_size_usd, max_price_threshold,
-    poll_interval_ms, telegram_bot_token, telegram_chat_id, telegram_enabled,
-    is_gambling_market, max_wallet_age_hours,
+    max_unique_markets, min_trade_size_usd, max_price_threshold, poll_interval_ms,
+    is_gambling_market, max_wallet_age_hours, split_order_window_secs,
+    cluster_min_wallets, cluster_window_secs, cluster_min_value_usd, cluster_min_trade_usd,
+    exit_min_value_usd,
+    link_co_trade_secs, link_min_co_trades,
+    record_max_markets,
+    watchlist_auto_add, Thresholds,
+    market_maker_detection, market_maker_min_fills, market_maker_min_maker_ratio,
+    market_maker_min_two_sided_ratio, followup_interval_secs, followup_max_alerts, record_trades,
 };
-use types::{AlertLevel, SuspectTrade, Trade, UserStats};
+use dedup::AlertDedup;
+use digest::{Digest, DigestSchedule, FunnelStats};
+use graph::{LinkParams, WalletGraph};
+use market_watchlist::MarketWatchlist;
+use notify::Dispatcher;
+use outcomes::{bet_context, BetContext};
+use scoring::linkage_points;
+use record::{calculate_track_record, traded_condition_ids};
+use types::{AlertLevel, Detector, Event, Market, SuspectTrade, Trade, UserActivity, UserStats};
+use watchlist::{auto_watch, Watchlist};
 
 // ============================================================================
-// STATE (simplified - no more cluster/volume noise)
+// STATE
 // ============================================================================
 
 struct TrackerState {
     processed_trade_ids: HashSet<String>,
-    user_stats_cache: HashMap<String, (UserStats, Instant)>,
+    /// Wallet stats, with the raw activity of wallets light enough to need a
+    /// track record (taken once the record is computed)
+    user_stats_cache: HashMap<String, (UserStats, Option<Vec<UserActivity>>, Instant)>,
+    market_cache: HashMap<String, (Option<Market>, Instant)>,
+    event_cache: HashMap<String, (Option<Event>, Instant)>,
+    split_orders: SplitOrderAggregator,
+    clusters: ClusterDetector,
+    anomalies: MarketAnomalyDetector,
+    /// Wallet links and flagged wallets (persisted)
+    graph: WalletGraph,
+    /// Wallets whose every trade is alerted (reloaded each poll for CLI edits)
+    watchlist: Watchlist,
+    /// Insider-prone markets with more sensitive thresholds (reloaded each poll)
+    market_watchlist: MarketWatchlist,
+    chain: Option<Box<dyn ChainProvider>>,
+    address_labels: HashMap<String, AddressLabel>,
+    /// Known market makers and funds that are never alerted on
+    allowlist: Allowlist,
+    market_maker_cache: HashMap<String, (MarketMakerStats, Instant)>,
+    /// When past alerts were last followed up
+    last_followup: Option<Instant>,
+    /// Open alerts that repeats of the same position are merged into (persisted)
+    alert_dedup: AlertDedup,
+    /// Funnel counts and timing of periodic digests (None when disabled)
+    digest: Option<DigestSchedule>,
+    /// Fans alerts out to the configured sinks
+    dispatcher: Dispatcher,
     poll_count: u64,
 }
 
 impl TrackerState {
-    fn new() -> Self {
+    fn new(dispatcher: Dispatcher, digest: Option<DigestSchedule>) -> Self {
         Self {
             processed_trade_ids: HashSet::new(),
             user_stats_cache: HashMap::new(),
+            market_cache: HashMap::new(),
+            event_cache: HashMap::new(),
+            split_orders: SplitOrderAggregator::new(),
+            clusters: ClusterDetector::new(),
+            anomalies: MarketAnomalyDetector::new(),
+            graph: WalletGraph::load(),
+            watchlist: Watchlist::load(),
+            market_watchlist: MarketWatchlist::load(),
+            chain: chain::provider_from_config(),
+            address_labels: load_address_labels(),
+            allowlist: Allowlist::load(),
+            market_maker_cache: HashMap::new(),
+            last_followup: None,
+            alert_dedup: AlertDedup::load(),
+            digest,
+            dispatcher,
             poll_count: 0,
         }
     }
 }
 
 const USER_CACHE_TTL_SECS: u64 = 60;
+const MARKET_CACHE_TTL_SECS: u64 = 600;
+const MARKET_MAKER_CACHE_TTL_SECS: u64 = 3600;
 
 // ============================================================================
 // MAIN
@@ -55,6 +153,17 @@ const USER_CACHE_TTL_SECS: u64 = 60;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
+
+    match cli::parse_args()? {
+        Command::Run => {}
+        Command::Wallet { address, json } => return profile::run_wallet_command(&address, json).await,
+        Command::Watch(action) => return watchlist::run_watch_command(action),
+        Command::WatchMarket(action) => return market_watchlist::run_market_watch_command(action),
+        Command::Report { json, refresh } => return report::run_report_command(json, refresh).await,
+        Command::Backtest(args) => return backtest::run_backtest_command(args).await,
+        Command::DeadLetters { replay } => return notify::run_dead_letter_command(replay).await,
+    }
+
     print_banner();
 
     let running = Arc::new(AtomicBool::new(true));
@@ -67,15 +176,34 @@ async fn main() -> anyhow::Result<()> {
     .expect("Error setting Ctrl-C handler");
 
     let mut client = ApiClient::new();
-    let mut state = TrackerState::new();
+    let mut state = TrackerState::new(Dispatcher::from_config()?, DigestSchedule::from_config()?);
+
+    println!("{} Alert sinks: {}\n", "📣".cyan(), state.dispatcher.describe_sinks().join(", "));
+    if let Some(digest) = &state.digest {
+        println!(
+            "{} Digest: {} → {}\n",
+            "🗞️".cyan(),
+            digest.describe(),
+            state.dispatcher.describe_digest_sinks().join(", ")
+        );
+    }
+    state.dispatcher.send_tests().await;
+    if state.dispatcher.pending_retries() > 0 {
+        println!("{} Resuming {} queued alert deliveries\n", "🔁".cyan(), state.dispatcher.pending_retries());
+    }
+    state.dispatcher.spawn_retry_worker();
 
-    // Send test message to Telegram if configured
-    if telegram_enabled() {
-        println!("{} Sending test message to Telegram...", "📱".cyan());
-        match send_telegram_test().await {
-            Ok(_) => println!("{} Telegram test successful! Check your chat.\n", "✅".green()),
-            Err(e) => eprintln!("{} Telegram test failed: {}\n", "❌".red(), e),
-        }
+    if let Some(chain) = &state.chain {
+        println!("{} Funding tracing via {} provider\n", "🔗".cyan(), chain.name());
+    }
+    if state.watchlist.count() > 0 {
+        println!("{} Watching {} wallets\n", "👀".cyan(), state.watchlist.count());
+    }
+    if state.allowlist.count() > 0 {
+        println!("{} Allowlisted {} wallets/pseudonyms\n", "🔇".cyan(), state.allowlist.count());
+    }
+    if state.market_watchlist.count() > 0 {
+        println!("{} Watching {} markets with lowered thresholds\n", "👀".cyan(), state.market_watchlist.count());
     }
 
     println!("{} Starting trade monitoring...\n", "🚀".green());
@@ -84,9 +212,16 @@ async fn main() -> anyhow::Result<()> {
         if let Err(e) = poll_trades(&mut client, &mut state).await {
             eprintln!("{} Poll error: {}", "❌".red(), e);
         }
+        follow_up_alerts(&mut client, &mut state).await;
+        send_digest_if_due(&mut state);
         sleep(Duration::from_millis(poll_interval_ms())).await;
     }
 
+    if let Some(digest) = &mut state.digest {
+        digest.flush();
+    }
+    state.dispatcher.drain().await;
+    state.dispatcher.flush().await;
     println!("\n{} Tracker stopped gracefully.", "👋".cyan());
     Ok(())
 }
@@ -121,380 +256,812 @@ async fn poll_trades(client: &mut ApiClient, state: &mut TrackerState) -> anyhow
 
     let new_count = new_trades.len();
 
+    // Keep the raw stream for backtesting
+    if record_trades() {
+        if let Err(e) = storage::append_jsonl_all("trades.jsonl", &new_trades) {
+            eprintln!("{} Failed to record trades: {:#}", "❌".red(), e);
+        }
+    }
+
+    // WATCHLIST: watched wallets bypass the whole filter chain (re-read only
+    // when the `watch` command changed the file)
+    state.watchlist.reload_if_changed();
+    let watched_trades: Vec<_> = new_trades
+        .iter()
+        .filter(|t| state.watchlist.contains(&t.proxy_wallet))
+        .cloned()
+        .collect();
+
+    // MARKET WATCHLIST: watched markets use Thresholds::watched_market()
+    state.market_watchlist.reload_if_changed();
+    let watched_markets = &state.market_watchlist;
+
     // ========================================================================
     // FILTER CHAIN - Real insider detection
     // ========================================================================
     
-    // FILTER 1: Exclude GAMBLING markets (crypto up/down, sports, hourly)
+    // FILTER 1: Exclude GAMBLING markets (crypto up/down, sports, hourly),
+    // unless an analyst explicitly watches the market
     let non_gambling: Vec<_> = new_trades
         .into_iter()
         .filter(|t| {
             let title = t.title.as_deref().unwrap_or("");
-            !is_gambling_market(title)
+            !is_gambling_market(title) || watched_markets.is_watched(t)
         })
         .collect();
     let non_gambling_count = non_gambling.len();
 
-    // FILTER 2: Large trades only ($5k+)
+    // Market anomalies see every non-gambling trade, independent of wallet
+    let mut market_trades = non_gambling.clone();
+    market_trades.sort_by_key(|t| t.timestamp);
+
+    // negRisk candidates priced above the threshold can still be long shots
+    // in their event: judge those in context before the odds filters run
+    let long_shots = neg_risk_long_shots(client, state, &non_gambling).await;
+    let watched_markets = &state.market_watchlist;
+    let is_long_shot = |t: &Trade| {
+        t.effective_price() < watched_markets.thresholds(t).max_price || long_shots.contains(&t.unique_id())
+    };
+
+    // Cluster candidates: every non-dust contrarian taker BUY, regardless of size.
+    // Allowlisted wallets never join one: a cluster alert can't be capped per member.
+    let cluster_min_trade = cluster_min_trade_usd();
+    let mut cluster_candidates: Vec<_> = non_gambling
+        .iter()
+        .filter(|t| {
+            t.is_taker_buy()
+                && is_long_shot(t)
+                && t.value_usd() >= cluster_min_trade
+                && state.allowlist.lookup(t).is_none()
+        })
+        .cloned()
+        .collect();
+
+    // FILTER 2: Large trades only ($5k+) - smaller fills go to split-order aggregation
     let min_size = min_trade_size_usd();
-    let large_trades: Vec<_> = non_gambling
+    let (large_trades, mut small_trades): (Vec<_>, Vec<_>) = non_gambling
         .into_iter()
-        .filter(|t| t.value_usd() >= min_size)
-        .collect();
+        .partition(|t| t.value_usd() >= watched_markets.thresholds(t).min_trade_size_usd);
     let large_count = large_trades.len();
 
-    // FILTER 3: TAKER BUY only (aggressive)
+    // FILTER 3: TAKER orders only (aggressive BUY, or SELL of the favourite)
     let aggressive_trades: Vec<_> = large_trades
         .into_iter()
-        .filter(|t| t.is_taker_buy())
+        .filter(|t| t.is_taker_buy() || t.is_taker_sell())
         .collect();
 
-    // FILTER 4: CONTRARIAN odds (< 35%)
-    let max_price = max_price_threshold();
+    // FILTER 4: CONTRARIAN odds (< 35%) - sells count at the complement price,
+    // negRisk candidates at their share of the event
     let contrarian_trades: Vec<_> = aggressive_trades
         .into_iter()
-        .filter(|t| t.price < max_price)
+        .filter(|t| is_long_shot(t))
         .collect();
     let contrarian_count = contrarian_trades.len();
 
-    // Analyze contrarian trades for fresh wallet activity
+    // Suppressed wallets are dropped before any detector spends API calls on them
+    let contrarian_trades: Vec<_> = contrarian_trades
+        .into_iter()
+        .filter(|t| !detect::is_suppressed(&state.allowlist, t))
+        .collect();
+
+    // Split-order candidates: small contrarian taker BUYs
+    small_trades.retain(|t| {
+        t.is_taker_buy()
+            && is_long_shot(t)
+            && !detect::is_suppressed(&state.allowlist, t)
+    });
+    small_trades.sort_by_key(|t| t.timestamp);
+
+    // Wallet graph: co-trading and position-sequence links
+    let link_params = LinkParams {
+        co_trade_secs: link_co_trade_secs(),
+        min_co_trades: link_min_co_trades(),
+    };
+    state.graph.observe_trades(&market_trades, &link_params);
+
     let mut suspects: Vec<SuspectTrade> = Vec::new();
 
+    // CLUSTERS: several fresh wallets buying the same outcome together
+    let cluster_params = ClusterParams {
+        min_wallets: cluster_min_wallets(),
+        window_secs: cluster_window_secs(),
+        min_value_usd: cluster_min_value_usd(),
+    };
+    cluster_candidates.sort_by_key(|t| t.timestamp);
+
+    for trade in &cluster_candidates {
+        let Some(user_stats) = get_user_stats(client, state, &trade.proxy_wallet).await else {
+            continue;
+        };
+        if fresh_wallet_age(&user_stats, &state.market_watchlist.thresholds(trade)).is_none() {
+            continue;
+        }
+        if detect_market_maker(client, state, &user_stats, &trade.proxy_wallet).await.is_some() {
+            continue;
+        }
+        if let Some(trades) = state.clusters.record(trade, &cluster_params) {
+            // Every member was looked up (and cached) when it joined
+            let mut members = Vec::new();
+            for wallet in cluster_wallets(&trades) {
+                members.extend(get_user_stats(client, state, &wallet).await);
+            }
+            suspects.push(detect::cluster_suspect(trades, user_stats, members));
+        }
+    }
+    state.clusters.prune(chrono::Utc::now().timestamp(), cluster_params.window_secs);
+
+    // Analyze contrarian trades for fresh wallet activity, unless a cluster
+    // alert already covers the trade
     for trade in &contrarian_trades {
+        if detect::covered_by_wallet_alert(&suspects, trade) {
+            continue;
+        }
         if let Some(suspect) = analyze_trade(client, state, trade.clone()).await {
             suspects.push(suspect);
         }
     }
 
+    // SPLIT ORDERS: small fills summed per wallet/market/outcome until they reach the size threshold
+    let window_secs = split_order_window_secs();
+    for trade in &small_trades {
+        let threshold = state.market_watchlist.thresholds(trade).min_trade_size_usd;
+        if let Some(fills) = state.split_orders.record(trade, window_secs, threshold) {
+            if let Some(suspect) = analyze_split_order(client, state, fills).await {
+                suspects.push(suspect);
+            }
+        }
+    }
+    state.split_orders.prune(chrono::Utc::now().timestamp(), window_secs);
+
+    // EXITS: previously flagged wallets selling out
+    let exit_min = exit_min_value_usd();
+    for trade in &market_trades {
+        if !trade.is_taker_sell() || trade.value_usd() < exit_min {
+            continue;
+        }
+        if detect::covered_by_wallet_alert(&suspects, trade) {
+            continue;
+        }
+        let Some(flagged_level) = state.graph.flagged_level(&trade.proxy_wallet) else {
+            continue;
+        };
+        if let Some(suspect) = analyze_exit(client, state, trade.clone(), flagged_level).await {
+            suspects.push(suspect);
+        }
+    }
+
+    // MARKET ANOMALIES: volume spikes and price drift, any wallet
+    let anomaly_params = AnomalyParams::from_config();
+    for trade in &market_trades {
+        for anomaly in state.anomalies.record(trade, &anomaly_params) {
+            if let Some(suspect) = analyze_anomaly(client, state, anomaly).await {
+                suspects.push(suspect);
+            }
+        }
+    }
+    state.anomalies.prune(chrono::Utc::now().timestamp(), &anomaly_params);
+
+    // WATCHED WALLETS: everything not already covered by a wallet-level alert
+    let watched_count = watched_trades.len();
+    for trade in watched_trades {
+        if !detect::covered_by_wallet_alert(&suspects, &trade) {
+            suspects.push(analyze_watched(client, state, trade).await);
+        }
+    }
+
+    // WATCHED MARKETS: optionally any fresh-wallet trade, whatever its size or odds
+    for trade in &market_trades {
+        if !state.market_watchlist.alert_any_fresh(trade) || detect::covered_by_wallet_alert(&suspects, trade) {
+            continue;
+        }
+        if let Some(suspect) = analyze_watched_market(client, state, trade.clone()).await {
+            suspects.push(suspect);
+        }
+    }
+
+    // Drop or cap allowlisted wallets and market makers before scoring, so
+    // suppressed alerts cost no market lookups
+    let mut allowed: Vec<SuspectTrade> = Vec::new();
+    for suspect in suspects {
+        if let Some(suspect) = apply_allowlist(client, state, suspect).await {
+            allowed.push(suspect);
+        }
+    }
+
+    // Score suspects with market context (time to resolution)
+    let mut scored: Vec<SuspectTrade> = Vec::new();
+    for suspect in allowed {
+        if let Some(suspect) = score_suspect(client, state, suspect).await {
+            scored.push(suspect);
+        }
+    }
+
+    // Escalate wallets linked to previously flagged ones; capped allowlist
+    // entries stay LOW
+    let mut suspects = scored;
+    for suspect in suspects
+        .iter_mut()
+        .filter(|s| s.detector.is_wallet_level() && state.allowlist.lookup(&s.trade).is_none())
+    {
+        escalate_linked(&state.graph, suspect);
+    }
+
+    // Flag this batch so their exits and future links are watched
+    // Watched-wallet activity is not itself suspicious, so it doesn't flag
+    for suspect in suspects
+        .iter()
+        .filter(|s| s.detector.is_wallet_level() && s.detector != Detector::WatchedWallet)
+    {
+        state.graph.flag(&suspect.trade.proxy_wallet, suspect.alert_level);
+        if suspect.detector == Detector::Cluster {
+            for fill in &suspect.fills {
+                state.graph.flag(&fill.proxy_wallet, suspect.alert_level);
+            }
+        }
+    }
+    if let Err(e) = state.graph.save_if_dirty() {
+        eprintln!("{} Failed to save wallet graph: {:#}", "❌".red(), e);
+    }
+
+    // Keep watching wallets behind HIGH alerts
+    if watchlist_auto_add() {
+        for suspect in suspects.iter().filter(|s| {
+            s.alert_level == AlertLevel::High
+                && s.detector.is_wallet_level()
+                && s.detector != Detector::WatchedWallet
+        }) {
+            let note = format!("auto: {} HIGH alert", suspect.detector);
+            auto_watch(&mut state.watchlist, &suspect.trade.proxy_wallet, &note);
+            if suspect.detector == Detector::Cluster {
+                for fill in &suspect.fills {
+                    auto_watch(&mut state.watchlist, &fill.proxy_wallet, &note);
+                }
+            }
+        }
+        if let Err(e) = state.watchlist.save_if_dirty() {
+            eprintln!("{} Failed to save watchlist: {:#}", "❌".red(), e);
+        }
+    }
+
+    // Repeats of an open position are merged into its alert, not re-sent
+    let now = chrono::Utc::now().timestamp();
+    let (suspects, repeat_count) = state.alert_dedup.admit(suspects, now);
+
     // Log poll summary
     println!(
-        "[POLL #{}] New: {} | Non-gambling: {} | Large(${:.0}k+): {} | Contrarian: {} | 🎯 INSIDERS: {}",
+        "[POLL #{}] New: {} | Non-gambling: {} | Large(${:.0}k+): {} | Contrarian: {} | Watched: {} | Repeats: {} | 🎯 INSIDERS: {}",
         state.poll_count,
         new_count,
         non_gambling_count,
         min_size / 1000.0,
         large_count,
         contrarian_count,
+        watched_count,
+        repeat_count,
         suspects.len()
     );
+    if let Some(digest) = &mut state.digest {
+        digest.record_poll(&FunnelStats {
+            polls: 1,
+            trades: new_count,
+            non_gambling: non_gambling_count,
+            large: large_count,
+            contrarian: contrarian_count,
+            watched: watched_count,
+            repeats: repeat_count,
+            alerts: suspects.len(),
+        });
+    }
 
     // Alert for each suspect
     for suspect in suspects {
-        alert_suspect(&suspect);
+        state.dispatcher.dispatch(&suspect);
+        if let Err(e) = record_alert(&suspect) {
+            eprintln!("{} Failed to save alert: {:#}", "❌".red(), e);
+        }
+    }
+
+    // Report positions that grew since their alert
+    for update in state.alert_dedup.take_updates(now) {
+        state.dispatcher.dispatch_update(&update);
+    }
+    if let Err(e) = state.alert_dedup.save_if_dirty() {
+        eprintln!("{} Failed to save open alerts: {:#}", "❌".red(), e);
     }
 
     Ok(())
 }
 
+/// Periodically check how past alerts played out (prices, resolutions)
+async fn follow_up_alerts(client: &mut ApiClient, state: &mut TrackerState) {
+    let interval = Duration::from_secs(followup_interval_secs());
+    if state.last_followup.is_some_and(|last| last.elapsed() < interval) {
+        return;
+    }
+    state.last_followup = Some(Instant::now());
+
+    match followup::update_outcomes(client, followup_max_alerts()).await {
+        Ok(summary) if summary.newly_resolved > 0 => println!(
+            "{} Followed up {} alerts, {} newly resolved (see `report`)",
+            "📒".cyan(),
+            summary.checked,
+            summary.newly_resolved
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("{} Alert follow-up failed: {:#}", "❌".red(), e),
+    }
+
+    // Mark the messages of resolved alerts as correct or incorrect
+    match followup::load_outcomes() {
+        Ok(outcomes) => state.dispatcher.dispatch_resolutions(&outcomes),
+        Err(e) => eprintln!("{} Failed to load alert outcomes: {:#}", "❌".red(), e),
+    }
+}
+
+/// Send the digest of the period that just ended, if one did
+fn send_digest_if_due(state: &mut TrackerState) {
+    let Some(schedule) = &mut state.digest else {
+        return;
+    };
+    let Some((start, end, funnel)) = schedule.take_due(chrono::Utc::now().timestamp()) else {
+        return;
+    };
+    match Digest::build(start, end, funnel) {
+        Ok(digest) => {
+            state.dispatcher.dispatch_digest(&digest);
+        }
+        Err(e) => eprintln!("{} Failed to build digest: {:#}", "❌".red(), e),
+    }
+}
+
 // ============================================================================
 // TRADE ANALYSIS
 // ============================================================================
+//
+// These gather what the detectors need (wallet stats, market, bet context)
+// from the API and caches; the decisions themselves live in `detect`.
 
 async fn analyze_trade(
     client: &mut ApiClient,
     state: &mut TrackerState,
     trade: Trade,
 ) -> Option<SuspectTrade> {
-    let wallet_address = &trade.proxy_wallet;
+    let user_stats = get_user_stats(client, state, &trade.proxy_wallet).await?;
+    let thresholds = state.market_watchlist.thresholds(&trade);
+    let now = chrono::Utc::now().timestamp();
+
+    // The track record costs extra lookups, and only matters for wallets that aren't fresh
+    let user_stats = match user_stats.fresh_age(&thresholds, now) {
+        Some(_) => user_stats,
+        None => with_track_record(client, state, user_stats).await,
+    };
+    detect::wallet_signal(&user_stats, &thresholds, now)?;
 
-    // Check cache
-    let now = Instant::now();
-    let user_stats = if let Some((cached, timestamp)) = state.user_stats_cache.get(wallet_address) {
-        if now.duration_since(*timestamp).as_secs() < USER_CACHE_TTL_SECS {
-            cached.clone()
-        } else {
-            let activities = client.fetch_user_activity(wallet_address).await.ok()?;
-            let stats = ApiClient::calculate_user_stats(wallet_address, &activities);
-            state.user_stats_cache.insert(wallet_address.clone(), (stats.clone(), now));
-            stats
-        }
-    } else {
-        let activities = client.fetch_user_activity(wallet_address).await.ok()?;
-        let stats = ApiClient::calculate_user_stats(wallet_address, &activities);
-        state.user_stats_cache.insert(wallet_address.clone(), (stats.clone(), now));
-        stats
+    let context = get_bet_context(client, state, &trade).await;
+    detect::evaluate_trade(trade, user_stats, &thresholds, context.as_ref(), now)
+}
+
+/// Evaluate a sell by a wallet that was flagged earlier
+async fn analyze_exit(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    trade: Trade,
+    flagged_level: AlertLevel,
+) -> Option<SuspectTrade> {
+    let user_stats = get_user_stats(client, state, &trade.proxy_wallet).await?;
+    Some(detect::exit_suspect(trade, user_stats, flagged_level))
+}
+
+/// Evaluate an aggregated split-order position that crossed the size threshold
+async fn analyze_split_order(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    fills: Vec<Trade>,
+) -> Option<SuspectTrade> {
+    let trade = fills.last()?;
+    let user_stats = get_user_stats(client, state, &trade.proxy_wallet).await?;
+    let thresholds = state.market_watchlist.thresholds(trade);
+    detect::evaluate_split_order(fills, user_stats, &thresholds, chrono::Utc::now().timestamp())
+}
+
+/// Alert on any trade by a watched wallet
+async fn analyze_watched(client: &mut ApiClient, state: &mut TrackerState, trade: Trade) -> SuspectTrade {
+    let user_stats = match get_user_stats(client, state, &trade.proxy_wallet).await {
+        Some(stats) => stats,
+        None => ApiClient::calculate_user_stats(&trade.proxy_wallet, &[]),
     };
+    detect::watched_suspect(trade, user_stats)
+}
 
-    // Limit cache size
-    if state.user_stats_cache.len() > 1000 {
-        if let Some(key) = state.user_stats_cache.keys().next().cloned() {
-            state.user_stats_cache.remove(&key);
+/// Alert on a fresh wallet trading in a watched market, at any size or odds
+async fn analyze_watched_market(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    trade: Trade,
+) -> Option<SuspectTrade> {
+    let user_stats = get_user_stats(client, state, &trade.proxy_wallet).await?;
+    let thresholds = state.market_watchlist.thresholds(&trade);
+    detect::evaluate_watched_market(trade, user_stats, &thresholds, chrono::Utc::now().timestamp())
+}
+
+/// Turn a market anomaly into an alert, attributed to its largest taker buyer
+async fn analyze_anomaly(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    anomaly: Anomaly,
+) -> Option<SuspectTrade> {
+    let trade = detect::anomaly_trade(&anomaly)?;
+    let user_stats = match get_user_stats(client, state, &trade.proxy_wallet).await {
+        Some(stats) => stats,
+        None => ApiClient::calculate_user_stats(&trade.proxy_wallet, &[]),
+    };
+    Some(detect::anomaly_suspect(anomaly, trade, user_stats))
+}
+
+/// Look up the market and bet context, then score the suspect (see
+/// `detect::score_suspect`)
+async fn score_suspect(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    suspect: SuspectTrade,
+) -> Option<SuspectTrade> {
+    let market = match suspect.trade.condition_id.clone() {
+        Some(cid) => get_market(client, state, &cid).await,
+        None => None,
+    };
+    let context = get_bet_context(client, state, &suspect.trade).await;
+    let thresholds = state.market_watchlist.thresholds(&suspect.trade);
+    let watched_market = state.market_watchlist.is_watched(&suspect.trade);
+    detect::score_suspect(
+        suspect,
+        market,
+        context,
+        &thresholds,
+        watched_market,
+        chrono::Utc::now().timestamp(),
+    )
+}
+
+/// IDs of trades priced at or above their contrarian threshold that are long
+/// shots in their negRisk event (a candidate's share of the field can be well
+/// below its raw price). Markets are fetched in batches, events only for
+/// negRisk markets.
+async fn neg_risk_long_shots(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    trades: &[Trade],
+) -> HashSet<String> {
+    let priced_out: Vec<&Trade> = trades
+        .iter()
+        .filter(|t| t.effective_price() >= state.market_watchlist.thresholds(t).max_price)
+        .collect();
+    let ids: Vec<String> = priced_out
+        .iter()
+        .filter_map(|t| t.condition_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let markets = get_markets(client, state, &ids).await;
+
+    let mut long_shots = HashSet::new();
+    for trade in priced_out {
+        let neg_risk = trade
+            .condition_id
+            .as_ref()
+            .and_then(|cid| markets.get(cid))
+            .is_some_and(|m| m.neg_risk == Some(true));
+        if !neg_risk {
+            continue;
+        }
+        let max_price = state.market_watchlist.thresholds(trade).max_price;
+        if get_bet_context(client, state, trade).await.is_some_and(|c| c.is_contrarian(max_price)) {
+            long_shots.insert(trade.unique_id());
         }
     }
+    long_shots
+}
 
-    let max_markets = max_unique_markets();
-    let max_age_hours = max_wallet_age_hours();
-
-    // CRITERIA 1: Fresh Wallet (Few Markets)
-    if user_stats.unique_markets <= max_markets {
-        
-        // CRITERIA 2: Fresh Wallet (Time)
-        // If we have activity data, ensure the wallet is young (created/first active recently)
-        let age_info = if let Some(first_act) = user_stats.first_activity_timestamp {
-            let now_ts = chrono::Utc::now().timestamp();
-            let age_hours = (now_ts - first_act) / 3600;
-            
-            if age_hours > max_age_hours as i64 {
-                return None; // Wallet is too old (> 24h)
-            }
-            format!("{}h old", age_hours)
-        } else {
-            "New".to_string()
-        };
+/// Work out what a trade is betting on, using the market and (for negRisk
+/// groups) its sibling markets
+async fn get_bet_context(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    trade: &Trade,
+) -> Option<BetContext> {
+    let market = get_market(client, state, trade.condition_id.as_deref()?).await?;
+    let event = match (market.neg_risk, trade.event_slug.as_deref()) {
+        (Some(true), Some(slug)) => get_event(client, state, slug).await,
+        _ => None,
+    };
+    Some(bet_context(trade, &market, event.as_ref()))
+}
 
-        let value_usd = trade.value_usd();
-
-        let mut reasons = vec![
-            format!(
-                "Fresh Wallet ({} mkts, {})",
-                user_stats.unique_markets,
-                age_info
-            ),
-            "Taker BUY (aggressive)".to_string(),
-        ];
-
-        let alert_level = if value_usd >= 5000.0 {
-            reasons.push(format!("Large Position (${:.0})", value_usd));
-            AlertLevel::High
-        } else {
-            // Keep medium for smaller tests if any leak through
-            AlertLevel::Medium
-        };
+/// Fetch an event with its sibling markets, served from cache while fresh
+async fn get_event(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    slug: &str,
+) -> Option<Event> {
+    let now = Instant::now();
+    if let Some((cached, timestamp)) = state.event_cache.get(slug) {
+        if now.duration_since(*timestamp).as_secs() < MARKET_CACHE_TTL_SECS {
+            return cached.clone();
+        }
+    }
 
-        let reason = reasons.join(" | ");
+    let event = client.fetch_event(slug).await.ok().flatten();
+    state.event_cache.insert(slug.to_string(), (event.clone(), now));
 
-        return Some(SuspectTrade {
-            trade,
-            user_stats,
-            reason,
-            alert_level,
-        });
+    // Limit cache size
+    if state.event_cache.len() > 500 {
+        if let Some(key) = state.event_cache.keys().next().cloned() {
+            state.event_cache.remove(&key);
+        }
     }
 
-    None
+    event
 }
 
-// ============================================================================
-// ALERTING
-// ============================================================================
+/// Fetch market metadata, served from cache while fresh
+async fn get_market(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    condition_id: &str,
+) -> Option<Market> {
+    let now = Instant::now();
+    if let Some((cached, timestamp)) = state.market_cache.get(condition_id) {
+        if now.duration_since(*timestamp).as_secs() < MARKET_CACHE_TTL_SECS {
+            return cached.clone();
+        }
+    }
 
-fn alert_suspect(suspect: &SuspectTrade) {
-    let trade = &suspect.trade;
-    let user_stats = &suspect.user_stats;
+    let market = client.fetch_market(condition_id).await.ok().flatten();
+    state.market_cache.insert(condition_id.to_string(), (market.clone(), now));
 
-    let (emoji, level_colored) = match suspect.alert_level {
-        AlertLevel::High => ("🚨", "HIGH".red().bold()),
-        AlertLevel::Medium => ("⚠️", "MEDIUM".yellow().bold()),
-        AlertLevel::Low => ("📊", "LOW".cyan()),
-    };
+    // Limit cache size
+    if state.market_cache.len() > 1000 {
+        if let Some(key) = state.market_cache.keys().next().cloned() {
+            state.market_cache.remove(&key);
+        }
+    }
 
-    let divider = "═".repeat(65);
-    let market_title = trade.title.as_deref().unwrap_or("Unknown Market");
-    let outcome = trade.outcome.as_deref().unwrap_or(&trade.side);
-    let masked_wallet = mask_address(&user_stats.address);
-    let value_usd = trade.value_usd();
-    let price_pct = trade.price * 100.0;
-    let market_url = trade.market_url();
-    
-    // Format timestamp
-    let timestamp = chrono::DateTime::from_timestamp(trade.timestamp, 0)
-        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-        .unwrap_or_else(|| trade.timestamp.to_string());
-
-    println!();
-    println!("{}", divider.bright_white());
-    println!("{} {} [{}] {}", emoji, "INSIDER ALERT".bold(), level_colored, emoji);
-    println!("{}", divider.bright_white());
-    println!("📈 Market:    {}", market_title.white().bold());
-    println!("🎯 Outcome:   {}", outcome.green());
-    println!("👛 Wallet:    {}", masked_wallet.cyan());
-    println!("📝 Pseudonym: {}", trade.pseudonym.as_deref().unwrap_or("Anonymous"));
-    println!("💰 Value:     ${:.2}", value_usd);
-    println!("📊 Price:     {:.1}%", price_pct);
-    println!("🔍 Reason:    {}", suspect.reason.yellow());
-    println!("📅 Time:      {}", timestamp);
-    println!("🔗 Tx:        {}", trade.transaction_hash.as_deref().unwrap_or("N/A"));
-    println!();
-    println!("🛒 {} {}", "BUY NOW:".green().bold(), market_url.underline());
-    println!("{}", divider.bright_white());
-    println!();
-
-    // Telegram notification (PRIORITY)
-    if telegram_enabled() {
-        let suspect_clone = suspect.clone();
-        tokio::spawn(async move {
-            if let Err(e) = send_telegram_alert(&suspect_clone).await {
-                eprintln!("{} Telegram alert failed: {}", "❌".red(), e);
-            }
-        });
+    market
+}
+
+/// Fetch user stats, served from cache while fresh
+async fn get_user_stats(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    wallet_address: &str,
+) -> Option<UserStats> {
+    let now = Instant::now();
+    if let Some((cached, _, timestamp)) = state.user_stats_cache.get(wallet_address) {
+        if now.duration_since(*timestamp).as_secs() < USER_CACHE_TTL_SECS {
+            return Some(cached.clone());
+        }
     }
 
-    // Discord webhook
-    if let Some(webhook_url) = discord_webhook_url() {
-        let suspect_clone = suspect.clone();
-        tokio::spawn(async move {
-            if let Err(e) = send_discord_alert(&webhook_url, &suspect_clone).await {
-                eprintln!("{} Discord alert failed: {}", "❌".red(), e);
-            }
-        });
+    let activities = client.fetch_user_activity(wallet_address).await.ok()?;
+    let mut stats = ApiClient::calculate_user_stats(wallet_address, &activities);
+    stats.funding = trace_funding(state, wallet_address).await;
+    if let Some(funding) = &stats.funding {
+        state.graph.observe_funding(wallet_address, funding);
+    }
+    let activities = (stats.unique_markets <= record_max_markets()).then_some(activities);
+    state.user_stats_cache.insert(wallet_address.to_string(), (stats.clone(), activities, now));
+
+    // Limit cache size
+    if state.user_stats_cache.len() > 1000 {
+        if let Some(key) = state.user_stats_cache.keys().next().cloned() {
+            state.user_stats_cache.remove(&key);
+        }
     }
+
+    Some(stats)
 }
 
-async fn send_discord_alert(webhook_url: &str, suspect: &SuspectTrade) -> anyhow::Result<()> {
-    let trade = &suspect.trade;
-    let market_title = trade.title.as_deref().unwrap_or("Unknown Market");
-    let market_url = trade.market_url();
+/// Attach the wallet's track record (low-volume wallets only, cached with the stats)
+async fn with_track_record(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    mut user_stats: UserStats,
+) -> UserStats {
+    if user_stats.record.is_some() || user_stats.unique_markets > record_max_markets() {
+        return user_stats;
+    }
 
-    let color = match suspect.alert_level {
-        AlertLevel::High => 0xFF0000,
-        AlertLevel::Medium => 0xFFA500,
-        AlertLevel::Low => 0x00FF00,
+    // Reuse the activity fetched with the stats; refetch only if it was evicted
+    let cached = state
+        .user_stats_cache
+        .get_mut(&user_stats.address)
+        .and_then(|(_, activities, _)| activities.take());
+    let activities = match cached {
+        Some(activities) => activities,
+        None => match client.fetch_user_activity(&user_stats.address).await {
+            Ok(activities) => activities,
+            Err(_) => return user_stats,
+        },
     };
+    let ids = traded_condition_ids(&activities);
+    let markets = get_markets(client, state, &ids).await;
+    user_stats.record = Some(calculate_track_record(&activities, &markets, max_price_threshold()));
 
-    let embed = serde_json::json!({
-        "embeds": [{
-            "title": format!("{} Insider Alert [{}]", 
-                if suspect.alert_level == AlertLevel::High { "🚨" } else { "⚠️" },
-                suspect.alert_level
-            ),
-            "color": color,
-            "fields": [
-                { "name": "📈 Market", "value": market_title, "inline": false },
-                { "name": "🎯 Outcome", "value": trade.outcome.as_deref().unwrap_or(&trade.side), "inline": true },
-                { "name": "💰 Value", "value": format!("${:.2}", trade.value_usd()), "inline": true },
-                { "name": "👛 Wallet", "value": mask_address(&suspect.user_stats.address), "inline": true },
-                { "name": "📊 Lifetime Markets", "value": suspect.user_stats.unique_markets.to_string(), "inline": true },
-                { "name": "🔍 Reason", "value": &suspect.reason, "inline": false },
-                { "name": "🛒 Buy Link", "value": market_url, "inline": false }
-            ]
-        }]
-    });
+    if let Some((cached, _, _)) = state.user_stats_cache.get_mut(&user_stats.address) {
+        cached.record = user_stats.record.clone();
+    }
+    user_stats
+}
 
-    reqwest::Client::new()
-        .post(webhook_url)
-        .json(&embed)
-        .send()
-        .await?;
+/// Fetch several markets, serving cached ones and batching the rest
+async fn get_markets(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    condition_ids: &[String],
+) -> HashMap<String, Market> {
+    let now = Instant::now();
+    let mut markets = HashMap::new();
+    let mut missing = Vec::new();
+
+    for cid in condition_ids {
+        match state.market_cache.get(cid) {
+            Some((cached, timestamp)) if now.duration_since(*timestamp).as_secs() < MARKET_CACHE_TTL_SECS => {
+                if let Some(market) = cached {
+                    markets.insert(cid.clone(), market.clone());
+                }
+            }
+            _ => missing.push(cid.clone()),
+        }
+    }
 
-    Ok(())
+    for chunk in missing.chunks(20) {
+        let Ok(fetched) = client.fetch_markets(chunk).await else {
+            continue;
+        };
+        for market in fetched {
+            if let Some(cid) = market.condition_id.clone() {
+                state.market_cache.insert(cid.clone(), (Some(market.clone()), now));
+                markets.insert(cid, market);
+            }
+        }
+    }
+
+    markets
 }
 
-/// Send alert to Telegram
-async fn send_telegram_alert(suspect: &SuspectTrade) -> anyhow::Result<()> {
-    let token = telegram_bot_token().ok_or_else(|| anyhow::anyhow!("No Telegram token"))?;
-    let chat_id = telegram_chat_id().ok_or_else(|| anyhow::anyhow!("No Telegram chat ID"))?;
-    
-    let trade = &suspect.trade;
-    let market_title = trade.title.as_deref().unwrap_or("Unknown Market");
-    let outcome = trade.outcome.as_deref().unwrap_or(&trade.side);
-    let market_url = trade.market_url();
-    let value_usd = trade.value_usd();
-    let price_pct = trade.price * 100.0;
-    
-    let emoji = match suspect.alert_level {
-        AlertLevel::High => "🚨",
-        AlertLevel::Medium => "⚠️",
-        AlertLevel::Low => "📊",
+/// Escalate an alert whose wallet is linked to a previously flagged wallet
+fn escalate_linked(graph: &WalletGraph, suspect: &mut SuspectTrade) {
+    let linked = graph.linked_flagged(&suspect.trade.proxy_wallet);
+    let Some((link, level)) = linked
+        .iter()
+        .max_by_key(|(_, level)| matches!(level, AlertLevel::High))
+    else {
+        return;
     };
-    
-    // Format timestamp
-    let timestamp = chrono::DateTime::from_timestamp(trade.timestamp, 0)
-        .map(|dt| dt.format("%H:%M:%S UTC").to_string())
-        .unwrap_or_else(|| "Unknown".to_string());
-    
-    // Build Telegram message with HTML (more reliable than MarkdownV2)
-    let message = format!(
-        r#"{emoji} <b>INSIDER ALERT [{level}]</b> {emoji}
-
-📈 <b>Market:</b> {title}
-🎯 <b>Outcome:</b> {outcome}
-💰 <b>Value:</b> ${value:.2}
-📊 <b>Price:</b> {price:.1}%
-👛 <b>Wallet:</b> <code>{wallet}</code>
-🔍 <b>Reason:</b> {reason}
-⏰ <b>Time:</b> {time}
-
-🛒 <a href="{url}">BUY NOW</a>"#,
-        emoji = emoji,
-        level = suspect.alert_level,
-        title = escape_html(market_title),
-        outcome = escape_html(outcome),
-        value = value_usd,
-        price = price_pct,
-        wallet = &suspect.user_stats.address,
-        reason = escape_html(&suspect.reason),
-        time = timestamp,
-        url = market_url,
+
+    suspect.reason = format!(
+        "{} | Linked to flagged {} [{}] ({})",
+        suspect.reason,
+        mask_address(&link.wallet),
+        level,
+        link.reason
     );
-    
-    let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
-    
-    let payload = serde_json::json!({
-        "chat_id": chat_id,
-        "text": message,
-        "parse_mode": "HTML",
-        "disable_web_page_preview": false
-    });
-    
-    let response = reqwest::Client::new()
-        .post(&url)
-        .json(&payload)
-        .send()
-        .await?;
-    
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!("Telegram API error: {}", error_text));
+    if linked.len() > 1 {
+        suspect.reason = format!("{} +{} more", suspect.reason, linked.len() - 1);
     }
-    
-    Ok(())
+    suspect.score.add("linked", linkage_points(linked.len()));
+    suspect.alert_level = AlertLevel::High;
 }
 
-/// Escape special characters for Telegram HTML
-fn escape_html(text: &str) -> String {
-    text.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
+/// Drop or cap alerts on allowlisted wallets and detected market makers.
+/// Market-wide anomalies are left alone.
+async fn apply_allowlist(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    mut suspect: SuspectTrade,
+) -> Option<SuspectTrade> {
+    if !suspect.detector.is_wallet_level() {
+        return Some(suspect);
+    }
+    let wallet = mask_address(&suspect.trade.proxy_wallet);
+
+    if let Some(entry) = state.allowlist.lookup(&suspect.trade) {
+        match entry.action {
+            AllowAction::Suppress => {
+                println!(
+                    "{} Suppressed {} alert on {} (allowlisted: {})",
+                    "🔇".dimmed(),
+                    suspect.detector,
+                    wallet,
+                    entry.name
+                );
+                return None;
+            }
+            AllowAction::CapLow => {
+                detect::cap_low(&mut suspect, &entry.name);
+                return Some(suspect);
+            }
+        }
+    }
+
+    if let Some(stats) = detect_market_maker(client, state, &suspect.user_stats, &suspect.trade.proxy_wallet).await {
+        println!(
+            "{} Suppressed {} alert on {} (market maker: {})",
+            "🔇".dimmed(),
+            suspect.detector,
+            wallet,
+            stats.summary()
+        );
+        return None;
+    }
+    Some(suspect)
 }
 
-/// Send a test message to verify Telegram is configured correctly
-async fn send_telegram_test() -> anyhow::Result<()> {
-    let token = telegram_bot_token().ok_or_else(|| anyhow::anyhow!("No Telegram token"))?;
-    let chat_id = telegram_chat_id().ok_or_else(|| anyhow::anyhow!("No Telegram chat ID"))?;
-    
-    // Use HTML parse mode - much easier to work with than MarkdownV2
-    let message = r#"🎯 <b>Polymarket REAL Insider Tracker</b>
+/// The wallet's maker/taker stats, if they mark it as a market maker
+async fn detect_market_maker(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    user_stats: &UserStats,
+    wallet_address: &str,
+) -> Option<MarketMakerStats> {
+    let params = MarketMakerParams {
+        min_fills: market_maker_min_fills(),
+        min_maker_ratio: market_maker_min_maker_ratio(),
+        min_two_sided_ratio: market_maker_min_two_sided_ratio(),
+    };
+    // Fresh wallets rarely have enough fills to judge, so skip the lookup
+    if !market_maker_detection() || user_stats.total_trades < params.min_fills {
+        return None;
+    }
+    let stats = get_market_maker_stats(client, state, wallet_address).await?;
+    stats.is_market_maker(&params).then_some(stats)
+}
 
-✅ Bot connected!
+/// Maker/taker split of a wallet's recent fills, served from cache while fresh
+async fn get_market_maker_stats(
+    client: &mut ApiClient,
+    state: &mut TrackerState,
+    wallet_address: &str,
+) -> Option<MarketMakerStats> {
+    let now = Instant::now();
+    if let Some((cached, timestamp)) = state.market_maker_cache.get(wallet_address) {
+        if now.duration_since(*timestamp).as_secs() < MARKET_MAKER_CACHE_TTL_SECS {
+            return Some(cached.clone());
+        }
+    }
 
-Monitoring for:
-• Fresh wallets (≤2 prior markets)
-• Large trades (&gt;$5,000)
-• Contrarian bets (&lt;35% odds)
-• Excluded: crypto up/down, sports, hourly
+    let all_fills = client.fetch_user_trades(wallet_address, false).await.ok()?;
+    let taker_fills = client.fetch_user_trades(wallet_address, true).await.ok()?;
+    let stats = MarketMakerStats::from_fills(&all_fills, &taker_fills);
+    state.market_maker_cache.insert(wallet_address.to_string(), (stats.clone(), now));
 
-Alerts will appear here for REAL insider activity."#;
-    
-    let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
-    
-    let payload = serde_json::json!({
-        "chat_id": chat_id,
-        "text": message,
-        "parse_mode": "HTML"
-    });
-    
-    let response = reqwest::Client::new()
-        .post(&url)
-        .json(&payload)
-        .send()
-        .await?;
-    
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!("Telegram API error: {}", error_text));
+    // Limit cache size
+    if state.market_maker_cache.len() > 1000 {
+        if let Some(key) = state.market_maker_cache.keys().next().cloned() {
+            state.market_maker_cache.remove(&key);
+        }
     }
-    
-    Ok(())
+
+    Some(stats)
+}
+
+/// Look up and classify a wallet's first USDC funding transfer
+async fn trace_funding(state: &TrackerState, wallet_address: &str) -> Option<WalletFunding> {
+    let chain = state.chain.as_ref()?;
+    let transfer = match chain.first_funding(wallet_address).await {
+        Ok(transfer) => transfer?,
+        Err(e) => {
+            eprintln!("{} Funding lookup failed for {}: {:#}", "⚠️".yellow(), mask_address(wallet_address), e);
+            return None;
+        }
+    };
+
+    let source_flagged = state.graph.flagged_level(&transfer.from).is_some();
+    Some(classify_funding(transfer, &state.address_labels, source_flagged))
+}
+
+/// Check the fresh-wallet criteria as of now
+fn fresh_wallet_age(user_stats: &UserStats, thresholds: &Thresholds) -> Option<String> {
+    user_stats.fresh_age(thresholds, chrono::Utc::now().timestamp())
 }
 
 // ============================================================================
@@ -506,8 +1073,6 @@ fn print_banner() {
     let max_markets = max_unique_markets();
     let max_price = (max_price_threshold() * 100.0) as u32;
     let max_age = max_wallet_age_hours();
-    let discord_enabled = discord_webhook_url().is_some();
-    let tg_enabled = telegram_enabled();
 
     println!(
         r#"
@@ -521,9 +1086,7 @@ fn print_banner() {
 ║    • Experience:    ≤ {} prior markets                         ║
 ║    • Position:      ${:<8.0} (High conviction)             ║
 ║    • Odds:          < {}% (Contrarian bet)                     ║
-║    • Type:          Aggressive Taker BUY only                  ║
-║                                                                ║
-║  Alerts: Telegram {} | Discord {}                     ║
+║    • Type:          Taker BUY of long shot / SELL of favourite ║
 ╚═══════════════════════════════════════════════════════════════╝
 "#,
         "💎".cyan(),
@@ -531,9 +1094,7 @@ fn print_banner() {
         max_age,
         max_markets,
         min_size,
-        max_price,
-        if tg_enabled { "✓" } else { "✗" },
-        if discord_enabled { "✓" } else { "✗" }
+        max_price
     );
 }
 
@@ -0,0 +1,207 @@
+//! Market watchlist
+//!
+//! Analysts know which markets are insider-prone (pending court rulings,
+//! corporate announcements). Trades in a watched market, matched by condition
+//! ID or event slug, go through the filter chain with `Thresholds::watched_market`
+//! instead of the standard set, and can optionally alert on any fresh-wallet
+//! trade. Entries come from WATCHLIST_MARKETS and `DATA_DIR/market_watchlist.json`.
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{watched_market_alert_any_fresh, watchlist_markets, Thresholds};
+use crate::storage::{load_json, modified_at, save_json};
+use crate::types::Trade;
+
+const MARKET_WATCHLIST_FILE: &str = "market_watchlist.json";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MarketWatchEntry {
+    pub added_at: i64,
+    /// Alert on every fresh-wallet trade, whatever its size or odds
+    pub alert_any_fresh: bool,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct MarketWatchlist {
+    /// Condition ID or event slug (lowercased) -> entry
+    markets: BTreeMap<String, MarketWatchEntry>,
+    /// From WATCHLIST_MARKETS, never persisted
+    #[serde(skip)]
+    configured: Vec<String>,
+    #[serde(skip)]
+    dirty: bool,
+    /// Modification time of the file as last read or written
+    #[serde(skip)]
+    file_mtime: Option<SystemTime>,
+}
+
+impl MarketWatchlist {
+    /// Load the persisted watchlist plus the configured markets
+    pub fn load() -> Self {
+        let mut watchlist = match load_json::<MarketWatchlist>(MARKET_WATCHLIST_FILE) {
+            Ok(Some(watchlist)) => watchlist,
+            Ok(None) => Self::default(),
+            Err(e) => {
+                eprintln!("⚠️  Market watchlist not loaded, starting empty: {:#}", e);
+                Self::default()
+            }
+        };
+        watchlist.configured = watchlist_markets();
+        watchlist.file_mtime = modified_at(MARKET_WATCHLIST_FILE);
+        watchlist
+    }
+
+    /// Re-read the file if it changed since it was last read or written
+    /// (e.g. by the `watch-market` command). Unsaved changes are kept as they are.
+    pub fn reload_if_changed(&mut self) {
+        if !self.dirty && modified_at(MARKET_WATCHLIST_FILE) != self.file_mtime {
+            *self = Self::load();
+        }
+    }
+
+    /// Persist if anything changed since the last save
+    pub fn save_if_dirty(&mut self) -> Result<()> {
+        if self.dirty {
+            save_json(MARKET_WATCHLIST_FILE, self)?;
+            self.dirty = false;
+            self.file_mtime = modified_at(MARKET_WATCHLIST_FILE);
+        }
+        Ok(())
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.markets.contains_key(key) || self.configured.iter().any(|k| k == key)
+    }
+
+    /// The watchlist key a trade matches: its condition ID or its event slug
+    fn matching_key(&self, trade: &Trade) -> Option<String> {
+        [trade.condition_id.as_deref(), trade.event_slug.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(|k| k.to_lowercase())
+            .find(|k| self.contains_key(k))
+    }
+
+    pub fn is_watched(&self, trade: &Trade) -> bool {
+        self.matching_key(trade).is_some()
+    }
+
+    /// Whether any fresh-wallet trade in this trade's market should alert
+    pub fn alert_any_fresh(&self, trade: &Trade) -> bool {
+        match self.matching_key(trade) {
+            Some(key) => match self.markets.get(&key) {
+                Some(entry) => entry.alert_any_fresh,
+                None => watched_market_alert_any_fresh(),
+            },
+            None => false,
+        }
+    }
+
+    /// Thresholds that apply to a trade
+    pub fn thresholds(&self, trade: &Trade) -> Thresholds {
+        if self.is_watched(trade) {
+            Thresholds::watched_market()
+        } else {
+            Thresholds::standard()
+        }
+    }
+
+    /// Number of distinct watched markets
+    pub fn count(&self) -> usize {
+        self.configured
+            .iter()
+            .filter(|k| !self.markets.contains_key(*k))
+            .count()
+            + self.markets.len()
+    }
+
+    /// Add or update a market. Returns false if it was already watched as-is.
+    pub fn add(&mut self, key: &str, alert_any_fresh: bool) -> bool {
+        let key = key.to_lowercase();
+        if self.markets.get(&key).is_some_and(|e| e.alert_any_fresh == alert_any_fresh) {
+            return false;
+        }
+        self.markets.insert(
+            key,
+            MarketWatchEntry {
+                added_at: chrono::Utc::now().timestamp(),
+                alert_any_fresh,
+            },
+        );
+        self.dirty = true;
+        true
+    }
+
+    /// Remove a persisted market. Returns false if it wasn't on the list.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let removed = self.markets.remove(&key.to_lowercase()).is_some();
+        self.dirty |= removed;
+        removed
+    }
+}
+
+// ============================================================================
+// CLI
+// ============================================================================
+
+pub enum MarketWatchAction {
+    Add { key: String, alert_any_fresh: bool },
+    Remove(String),
+    List,
+}
+
+/// Run the `watch-market` command
+pub fn run_market_watch_command(action: MarketWatchAction) -> Result<()> {
+    let mut watchlist = MarketWatchlist::load();
+
+    match action {
+        MarketWatchAction::Add { key, alert_any_fresh } => {
+            if watchlist.add(&key, alert_any_fresh) {
+                let any_fresh = if alert_any_fresh { " (alerting on any fresh wallet)" } else { "" };
+                println!("{} Watching market {}{}", "👀".cyan(), key, any_fresh);
+            } else {
+                println!("{} {} is already watched", "ℹ️".cyan(), key);
+            }
+        }
+        MarketWatchAction::Remove(key) => {
+            if watchlist.remove(&key) {
+                println!("{} No longer watching market {}", "✅".green(), key);
+            } else if watchlist.contains_key(&key.to_lowercase()) {
+                println!("{} {} is set in WATCHLIST_MARKETS; remove it there", "⚠️".yellow(), key);
+            } else {
+                println!("{} {} is not watched", "ℹ️".cyan(), key);
+            }
+        }
+        MarketWatchAction::List => {
+            let t = Thresholds::watched_market();
+            println!("{} ({})", "👀 Watched markets".bold(), watchlist.count());
+            println!(
+                "   thresholds: ${:.0}+ | < {:.0}% odds | ≤ {} markets | < {}h old",
+                t.min_trade_size_usd,
+                t.max_price * 100.0,
+                t.max_unique_markets,
+                t.max_wallet_age_hours
+            );
+            let config_any_fresh = if watched_market_alert_any_fresh() { "  any fresh" } else { "" };
+            for key in &watchlist.configured {
+                if !watchlist.markets.contains_key(key) {
+                    println!("  {}  (WATCHLIST_MARKETS){}", key, config_any_fresh);
+                }
+            }
+            for (key, entry) in &watchlist.markets {
+                let added = chrono::DateTime::from_timestamp(entry.added_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+                    .unwrap_or_default();
+                let any_fresh = if entry.alert_any_fresh { "  any fresh" } else { "" };
+                println!("  {}  since {}{}", key, added, any_fresh);
+            }
+        }
+    }
+
+    watchlist.save_if_dirty()
+}
@@ -0,0 +1,51 @@
+//! Console sink: the full alert box on stdout
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::templates::{AlertContext, DigestContext, Templates, UpdateContext};
+use super::AlertSink;
+use crate::dedup::AlertUpdate;
+use crate::digest::Digest;
+use crate::types::SuspectTrade;
+
+pub const NAME: &str = "console";
+
+pub struct ConsoleSink {
+    templates: Arc<Templates>,
+}
+
+impl ConsoleSink {
+    pub fn new(templates: Arc<Templates>) -> Self {
+        Self { templates }
+    }
+}
+
+#[async_trait]
+impl AlertSink for ConsoleSink {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()> {
+        // Printed in one go so concurrent alerts don't interleave
+        print!("{}", self.templates.render("console", &AlertContext::from_suspect(suspect))?);
+        Ok(())
+    }
+
+    async fn send_digest(&self, digest: &Digest) -> Result<()> {
+        print!("{}", self.templates.render("console_digest", &DigestContext::new(digest))?);
+        Ok(())
+    }
+
+    fn sends_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_update(&self, update: &AlertUpdate) -> Result<()> {
+        print!("{}", self.templates.render("console_update", &UpdateContext::from_update(update))?);
+        Ok(())
+    }
+}
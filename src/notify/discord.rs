@@ -0,0 +1,142 @@
+//! Discord sink: one embed per alert via a channel webhook
+//!
+//! Messages are posted with `?wait=true` so Discord returns their ID; sent
+//! alerts are then edited in place as the position grows and once the market
+//! resolves (see `messages`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde_json::Value;
+
+use super::messages::{SentAlert, SentMessages};
+use super::templates::{AlertContext, DigestContext, Templates, UpdateContext};
+use super::{response_error, retry_after, AlertSink, RateLimited};
+use crate::dedup::AlertUpdate;
+use crate::digest::Digest;
+use crate::followup::AlertOutcome;
+use crate::types::SuspectTrade;
+
+pub struct DiscordSink {
+    client: Client,
+    name: String,
+    webhook_url: Url,
+    templates: Arc<Templates>,
+    messages: Arc<SentMessages>,
+}
+
+impl DiscordSink {
+    pub fn new(
+        client: Client,
+        name: String,
+        webhook_url: String,
+        templates: Arc<Templates>,
+        messages: Arc<SentMessages>,
+    ) -> Result<Self> {
+        let webhook_url = Url::parse(&webhook_url).with_context(|| format!("Invalid webhook URL for {}", name))?;
+        Ok(Self {
+            client,
+            name,
+            webhook_url,
+            templates,
+            messages,
+        })
+    }
+
+    /// Webhook URL for posting (`?wait=true`), or for editing message `id`.
+    /// Query parameters such as `thread_id` are kept.
+    fn url(&self, message_id: Option<&str>) -> Url {
+        let mut url = self.webhook_url.clone();
+        match message_id {
+            Some(id) => {
+                if let Ok(mut segments) = url.path_segments_mut() {
+                    segments.pop_if_empty().push("messages").push(id);
+                }
+            }
+            None => {
+                url.query_pairs_mut().append_pair("wait", "true");
+            }
+        }
+        url
+    }
+
+    /// Post a new message, or edit one; returns the message
+    async fn request(&self, message_id: Option<&str>, message: &Value) -> Result<Value> {
+        let method = if message_id.is_some() { Method::PATCH } else { Method::POST };
+        let response = self
+            .client
+            .request(method, self.url(message_id))
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited {
+                retry_after: retry_after(&response).unwrap_or(Duration::from_secs(1)),
+            }
+            .into());
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(response_error(status, format!("Discord webhook error: {}", error_text)));
+        }
+        Ok(response.json().await.unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl AlertSink for DiscordSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()> {
+        let embed = self.templates.render_json("discord", &AlertContext::from_suspect(suspect))?;
+        let message = self.request(None, &embed).await?;
+        if let Some(message_id) = message["id"].as_str() {
+            self.messages.record(&self.name, suspect, message_id.to_string());
+        }
+        Ok(())
+    }
+
+    async fn send_digest(&self, digest: &Digest) -> Result<()> {
+        let embed = self.templates.render_json("discord_digest", &DigestContext::new(digest))?;
+        self.request(None, &embed).await?;
+        Ok(())
+    }
+
+    fn sends_updates(&self) -> bool {
+        true
+    }
+
+    /// Edits the original alert when we have its message, else posts an update
+    async fn send_update(&self, update: &AlertUpdate) -> Result<()> {
+        if let Some(message_id) = self.messages.message_id(&self.name, &update.suspect.alert_id()) {
+            let context = AlertContext::from_suspect(&update.suspect).with_update(chrono::Utc::now().timestamp());
+            let embed = self.templates.render_json("discord", &context)?;
+            self.request(Some(&message_id), &embed).await?;
+            return Ok(());
+        }
+        let embed = self.templates.render_json("discord_update", &UpdateContext::from_update(update))?;
+        self.request(None, &embed).await?;
+        Ok(())
+    }
+
+    async fn send_resolution(&self, sent: &SentAlert, outcome: &AlertOutcome) -> Result<()> {
+        let Some(message_id) = sent.messages.get(&self.name) else {
+            return Ok(());
+        };
+        let mut context = AlertContext::from_suspect(&sent.suspect).with_resolution(outcome);
+        if let Some(updated_at) = sent.updated_at {
+            context = context.with_update(updated_at);
+        }
+        let embed = self.templates.render_json("discord", &context)?;
+        self.request(Some(message_id), &embed).await?;
+        Ok(())
+    }
+}
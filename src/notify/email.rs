@@ -0,0 +1,265 @@
+//! Email sink: HTML + plain-text alerts over SMTP
+//!
+//! With `batch_secs` > 0 alerts are collected and sent as one email per
+//! window; a failed batch is kept and goes out with the next one. The batch
+//! is saved to `DATA_DIR/email_batch_<sink>.json`, so alerts still held at
+//! exit (or whose final flush failed) go out on the next run.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Deserialize;
+
+use super::templates::{BatchContext, DigestContext, Templates};
+use super::AlertSink;
+use crate::digest::Digest;
+use crate::storage::{load_json, save_json};
+use crate::types::SuspectTrade;
+
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS (port 587)
+    #[default]
+    Starttls,
+    /// TLS from the start (port 465)
+    Tls,
+    /// Unencrypted, for local relays and test stubs only
+    None,
+}
+
+impl std::str::FromStr for SmtpTls {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "starttls" => Ok(SmtpTls::Starttls),
+            "tls" => Ok(SmtpTls::Tls),
+            "none" => Ok(SmtpTls::None),
+            other => Err(anyhow::anyhow!("Unknown SMTP TLS mode '{}' (starttls, tls, none)", other)),
+        }
+    }
+}
+
+/// SMTP server and envelope settings of one email sink
+#[derive(Debug, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+pub struct EmailSink {
+    inner: Arc<Mailer>,
+    batching: bool,
+}
+
+struct Mailer {
+    name: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    pending: Mutex<Vec<SuspectTrade>>,
+    /// Where `pending` is persisted
+    batch_file: String,
+    /// Held while a batch is being sent, so it never goes out twice
+    flushing: tokio::sync::Mutex<()>,
+    templates: Arc<Templates>,
+}
+
+/// e.g. "email_batch_email_desk.json" for sink "email:desk"
+fn batch_file(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("email_batch_{}.json", name)
+}
+
+/// Build the email of `<prefix>_subject`, `<prefix>_text` and `<prefix>_html`
+fn compose<T: serde::Serialize>(
+    templates: &Templates,
+    from: &Mailbox,
+    to: &[Mailbox],
+    prefix: &str,
+    context: &T,
+) -> Result<Message> {
+    let subject = templates.render(&format!("{}_subject", prefix), context)?;
+    let mut message = Message::builder().from(from.clone()).subject(subject.trim());
+    for to in to {
+        message = message.to(to.clone());
+    }
+    Ok(message.multipart(MultiPart::alternative_plain_html(
+        templates.render(&format!("{}_text", prefix), context)?,
+        templates.render(&format!("{}_html", prefix), context)?,
+    ))?)
+}
+
+impl EmailSink {
+    /// Build the sink; with a batch window, starts the background flusher
+    pub fn new(name: String, settings: SmtpSettings, batch: Option<Duration>, templates: Arc<Templates>) -> Result<Self> {
+        let mut builder = match settings.tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host),
+        }
+        .port(settings.port)
+        .timeout(Some(Duration::from_secs(20)));
+        if let (Some(username), Some(password)) = (settings.username, settings.password) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = settings
+            .from
+            .parse()
+            .with_context(|| format!("Invalid sender address '{}'", settings.from))?;
+        let to = settings
+            .to
+            .iter()
+            .map(|addr| addr.parse().with_context(|| format!("Invalid recipient address '{}'", addr)))
+            .collect::<Result<Vec<Mailbox>>>()?;
+        anyhow::ensure!(!to.is_empty(), "Email sink {} has no recipients", name);
+
+        let batch_file = batch_file(&name);
+        let pending = match load_json(&batch_file) {
+            Ok(pending) => pending.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("{} Failed to load {} batch: {:#}", "⚠️".yellow(), name, e);
+                Vec::new()
+            }
+        };
+        let inner = Arc::new(Mailer {
+            name,
+            transport: builder.build(),
+            from,
+            to,
+            pending: Mutex::new(pending),
+            batch_file,
+            flushing: tokio::sync::Mutex::new(()),
+            templates,
+        });
+
+        if let Some(window) = batch {
+            let mailer = inner.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(window).await;
+                    if let Err(e) = mailer.flush().await {
+                        eprintln!("{} {} batch failed, will retry: {:#}", "❌".red(), mailer.name, e);
+                    }
+                }
+            });
+        }
+
+        Ok(Self {
+            inner,
+            batching: batch.is_some(),
+        })
+    }
+}
+
+impl Mailer {
+    async fn deliver(&self, suspects: &[SuspectTrade]) -> Result<()> {
+        let context = BatchContext::new(suspects);
+        self.send_rendered("email", &context).await
+    }
+
+    /// Render `<prefix>_subject`, `<prefix>_text` and `<prefix>_html` and send them
+    async fn send_rendered<T: serde::Serialize + Sync>(&self, prefix: &str, context: &T) -> Result<()> {
+        let message = compose(&self.templates, &self.from, &self.to, prefix, context)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+
+    /// Add an alert to the batch
+    fn hold(&self, suspect: &SuspectTrade) -> Result<()> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.push(suspect.clone());
+        save_json(&self.batch_file, &*pending)
+    }
+
+    /// Send everything collected so far as one email. The batch stays saved
+    /// until it went out.
+    async fn flush(&self) -> Result<()> {
+        let _flushing = self.flushing.lock().await;
+        let batch = self.pending.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.deliver(&batch).await?;
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.drain(..batch.len());
+        save_json(&self.batch_file, &*pending)?;
+        println!("{} Emailed {} alerts via {}", "📧".green(), batch.len(), self.name);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AlertSink for EmailSink {
+    fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()> {
+        if self.batching {
+            return self.inner.hold(suspect);
+        }
+        self.inner.deliver(std::slice::from_ref(suspect)).await
+    }
+
+    /// Sent right away, never batched
+    async fn send_digest(&self, digest: &Digest) -> Result<()> {
+        self.inner.send_rendered("email_digest", &DigestContext::new(digest)).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.inner.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{suspect, trade};
+    use crate::types::{AlertLevel, Detector};
+
+    #[test]
+    fn batch_is_one_multipart_email_to_every_recipient() {
+        let templates = Templates::load().unwrap();
+        let from: Mailbox = "Insider Tracker <alerts@example.com>".parse().unwrap();
+        let to: Vec<Mailbox> = vec!["desk@example.com".parse().unwrap(), "ops@example.com".parse().unwrap()];
+        let mut first = trade("0xa", "BUY", 0.12, 50_000.0, 1_718_000_000);
+        first.title = Some("Will A & B happen?".to_string());
+        let second = trade("0xb", "BUY", 0.2, 30_000.0, 1_718_000_060);
+        let batch = [
+            suspect(first, AlertLevel::High, Detector::FreshWallet),
+            suspect(second, AlertLevel::Medium, Detector::FreshWallet),
+        ];
+
+        let message = compose(&templates, &from, &to, "email", &BatchContext::new(&batch)).unwrap();
+
+        let envelope = message.envelope();
+        assert_eq!(envelope.from().map(|a| a.to_string()).as_deref(), Some("alerts@example.com"));
+        assert_eq!(envelope.to().len(), 2);
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("multipart/alternative"));
+        assert!(raw.contains("text/plain"));
+        assert!(raw.contains("text/html"));
+        // Undo quoted-printable soft line breaks before looking into the parts
+        let unfolded = raw.replace("=\r\n", "");
+        assert!(unfolded.contains("Will A & B happen?"), "text part keeps the title as is");
+        assert!(unfolded.contains("Will A &amp; B happen?"), "HTML part escapes the title");
+    }
+}
@@ -0,0 +1,123 @@
+//! Messages sinks have sent, so they can be edited later
+//!
+//! Telegram and Discord return an ID for every message. The ID is kept per
+//! alert and sink in `DATA_DIR/sent_messages.json`, together with the alert
+//! as last shown. When the position grows the message is edited in place
+//! instead of posting an update, and once the market resolves it is edited
+//! to mark the alert correct or incorrect. A sink's entry is dropped once
+//! that edit succeeded, and every entry after MAX_AGE_DAYS.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_json, save_json};
+use crate::types::SuspectTrade;
+
+const SENT_MESSAGES_FILE: &str = "sent_messages.json";
+
+/// Alerts whose markets haven't resolved by then are no longer edited
+const MAX_AGE_DAYS: i64 = 90;
+
+/// An alert delivered to at least one sink that can edit it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SentAlert {
+    pub sent_at: i64,
+    /// The alert as last shown, grown position included
+    pub suspect: SuspectTrade,
+    /// When the position was last updated in place
+    pub updated_at: Option<i64>,
+    /// Message ID per sink name
+    pub messages: HashMap<String, String>,
+}
+
+#[derive(Default)]
+pub struct SentMessages {
+    /// Keyed by alert ID
+    alerts: Mutex<HashMap<String, SentAlert>>,
+}
+
+impl SentMessages {
+    /// Load the messages of the last runs, forgetting expired ones
+    pub fn load() -> Self {
+        let mut alerts: HashMap<String, SentAlert> = match load_json(SENT_MESSAGES_FILE) {
+            Ok(alerts) => alerts.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("{} Failed to load sent messages: {:#}", "⚠️".yellow(), e);
+                HashMap::new()
+            }
+        };
+        let cutoff = chrono::Utc::now().timestamp() - MAX_AGE_DAYS * 86400;
+        alerts.retain(|_, sent| sent.sent_at >= cutoff);
+        Self {
+            alerts: Mutex::new(alerts),
+        }
+    }
+
+    /// Remember the message a sink sent for an alert
+    pub fn record(&self, sink: &str, suspect: &SuspectTrade, message_id: String) {
+        let mut alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        alerts
+            .entry(suspect.alert_id())
+            .or_insert_with(|| SentAlert {
+                sent_at: chrono::Utc::now().timestamp(),
+                suspect: suspect.clone(),
+                updated_at: None,
+                messages: HashMap::new(),
+            })
+            .messages
+            .insert(sink.to_string(), message_id);
+        save(&alerts);
+    }
+
+    /// ID of the message a sink sent for an alert
+    pub fn message_id(&self, sink: &str, alert_id: &str) -> Option<String> {
+        let alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        alerts.get(alert_id)?.messages.get(sink).cloned()
+    }
+
+    /// Alerts with messages that can still be edited
+    pub fn alert_ids(&self) -> Vec<String> {
+        let alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        alerts.keys().cloned().collect()
+    }
+
+    /// Keep the grown position, so a later resolution edit still shows it
+    pub fn record_update(&self, suspect: &SuspectTrade, now_ts: i64) {
+        let mut alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(sent) = alerts.get_mut(&suspect.alert_id()) else {
+            return;
+        };
+        sent.suspect = suspect.clone();
+        sent.updated_at = Some(now_ts);
+        save(&alerts);
+    }
+
+    /// An alert's messages, for its final edit
+    pub fn get(&self, alert_id: &str) -> Option<SentAlert> {
+        let alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        alerts.get(alert_id).cloned()
+    }
+
+    /// Drop a sink's message once its final edit is done, and the alert
+    /// with its last message
+    pub fn forget(&self, alert_id: &str, sink: &str) {
+        let mut alerts = self.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(sent) = alerts.get_mut(alert_id) else {
+            return;
+        };
+        sent.messages.remove(sink);
+        if sent.messages.is_empty() {
+            alerts.remove(alert_id);
+        }
+        save(&alerts);
+    }
+}
+
+fn save(alerts: &HashMap<String, SentAlert>) {
+    if let Err(e) = save_json(SENT_MESSAGES_FILE, alerts) {
+        eprintln!("{} Failed to save sent messages: {:#}", "❌".red(), e);
+    }
+}
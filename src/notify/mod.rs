@@ -0,0 +1,769 @@
+//! Alert delivery
+//!
+//! Every alert goes through the `Dispatcher`, which fans it out to each
+//! configured `AlertSink` concurrently and reports per-sink delivery status.
+//! Sinks come from NOTIFIERS_FILE, so a sink type can have several instances
+//! (e.g. two Telegram chats); without the file, the console plus the
+//! TELEGRAM_*, DISCORD_WEBHOOK_URL, SLACK_WEBHOOK_URL, WEBHOOK_* and SMTP_* /
+//! EMAIL_* variables are used. All sinks share one
+//! HTTP client. A sink's optional `route` limits the alerts it receives (see
+//! `route`). Messages are rendered from templates (see `templates`). Failed
+//! deliveries go to a durable retry queue (see `queue`). Sinks that can edit
+//! their messages remember them (see `messages`). Periodic digests (see
+//! `crate::digest`) go to every sink, or those named in DIGEST_SINKS.
+
+mod console;
+mod discord;
+mod email;
+mod messages;
+mod payload;
+mod queue;
+mod route;
+mod slack;
+mod telegram;
+mod templates;
+mod webhook;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use colored::*;
+use reqwest::{Client, Response, StatusCode};
+use serde::Deserialize;
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::config::{
+    digest_sinks, discord_webhook_url, email_batch_secs, email_from, email_to, notifiers_file, slack_webhook_url, smtp_host,
+    smtp_password, smtp_port, smtp_tls, smtp_username, telegram_bot_token, telegram_chat_id, webhook_secret,
+    webhook_url,
+};
+use crate::dedup::AlertUpdate;
+use crate::digest::Digest;
+use crate::followup::AlertOutcome;
+use crate::types::{AlertLevel, SuspectTrade};
+
+pub use console::ConsoleSink;
+pub use discord::DiscordSink;
+pub use email::EmailSink;
+pub use queue::run_dead_letter_command;
+pub use route::Route;
+pub use slack::SlackSink;
+pub use telegram::TelegramSink;
+pub use webhook::WebhookSink;
+
+use email::{SmtpSettings, SmtpTls};
+use messages::{SentAlert, SentMessages};
+use templates::Templates;
+use queue::{persist, retry_delivery, FailureOutcome, RetryPolicy, RetryQueue};
+
+/// How long `drain` waits for deliveries in flight at exit
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    /// Instance name for logs, e.g. "telegram:ops"
+    fn name(&self) -> &str;
+
+    /// Deliver one alert
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()>;
+
+    /// Deliver a periodic digest (see `digest`)
+    async fn send_digest(&self, digest: &Digest) -> Result<()>;
+
+    /// Whether `send_test` does anything for this sink
+    fn sends_test(&self) -> bool {
+        false
+    }
+
+    /// Startup message confirming the sink is configured correctly
+    async fn send_test(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether `send_update` does anything for this sink
+    fn sends_updates(&self) -> bool {
+        false
+    }
+
+    /// Report that an alerted position grew (see `dedup`)
+    async fn send_update(&self, _update: &AlertUpdate) -> Result<()> {
+        Ok(())
+    }
+
+    /// Mark an alert this sink delivered as correct or incorrect, once its
+    /// market resolved
+    async fn send_resolution(&self, _sent: &SentAlert, _outcome: &AlertOutcome) -> Result<()> {
+        Ok(())
+    }
+
+    /// Deliver anything the sink is holding back (batched alerts)
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Error a sink returns when the service asks us to back off
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited, retry after {:.1}s", self.retry_after.as_secs_f64())
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Error retrying can't fix (rejected request, revoked token, broken
+/// template): the delivery is dead-lettered at once
+#[derive(Debug)]
+pub struct Permanent(pub String);
+
+impl fmt::Display for Permanent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Permanent {}
+
+/// Whether retrying can't help (see `Permanent`)
+fn is_permanent(error: &anyhow::Error) -> bool {
+    error.chain().any(|e| e.is::<Permanent>())
+}
+
+/// Error for an unsuccessful response: permanent for a 4xx other than 429
+fn response_error(status: StatusCode, message: String) -> anyhow::Error {
+    if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+        Permanent(message).into()
+    } else {
+        anyhow::anyhow!(message)
+    }
+}
+
+/// Wait requested by a 429: `Retry-After`, else the bucket reset (seconds, may be fractional)
+fn retry_after(response: &Response) -> Option<Duration> {
+    ["retry-after", "x-ratelimit-reset-after"].iter().find_map(|header| {
+        let secs: f64 = response.headers().get(*header)?.to_str().ok()?.parse().ok()?;
+        Some(Duration::from_secs_f64(secs.max(0.0)))
+    })
+}
+
+/// Outcome of one delivery attempt to one sink
+#[derive(Debug, Clone)]
+pub struct DeliveryStatus {
+    pub sink: String,
+    pub error: Option<String>,
+    pub elapsed: Duration,
+    /// When a failed delivery will be retried (None if delivered or given up)
+    pub retry_in: Option<Duration>,
+}
+
+impl DeliveryStatus {
+    pub fn delivered(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// ============================================================================
+// CONFIG
+// ============================================================================
+
+/// One entry of NOTIFIERS_FILE: `{ "sinks": [ { "type": "telegram", ... } ] }`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SinkConfig {
+    Console,
+    Telegram {
+        name: Option<String>,
+        /// Defaults to TELEGRAM_BOT_TOKEN
+        bot_token: Option<String>,
+        chat_id: String,
+    },
+    Discord {
+        name: Option<String>,
+        webhook_url: String,
+    },
+    Slack {
+        name: Option<String>,
+        webhook_url: String,
+        /// Only honoured by legacy incoming webhooks: Slack-app webhooks
+        /// always post to the channel chosen when the app was installed
+        channel: Option<String>,
+        /// e.g. ["<!here>", "<@U123ABC>"]
+        #[serde(default)]
+        mentions: Vec<String>,
+        #[serde(default = "default_mention_levels")]
+        mention_levels: Vec<AlertLevel>,
+    },
+    /// Any HTTP endpoint, receiving the versioned alert JSON
+    Webhook {
+        name: Option<String>,
+        url: String,
+        /// HMAC-SHA256 signing key
+        secret: Option<String>,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// Handlebars body template, inline or from a file
+        template: Option<String>,
+        template_file: Option<String>,
+        content_type: Option<String>,
+    },
+    Email {
+        name: Option<String>,
+        host: String,
+        #[serde(default = "smtp_port")]
+        port: u16,
+        #[serde(default)]
+        tls: SmtpTls,
+        username: Option<String>,
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+        /// One email per window of this many seconds (0 = per alert)
+        #[serde(default)]
+        batch_secs: u64,
+    },
+}
+
+fn default_mention_levels() -> Vec<AlertLevel> {
+    vec![AlertLevel::High]
+}
+
+/// A sink plus the alerts routed to it
+#[derive(Debug, Deserialize)]
+struct SinkEntry {
+    #[serde(flatten)]
+    sink: SinkConfig,
+    #[serde(default)]
+    route: Route,
+}
+
+impl From<SinkConfig> for SinkEntry {
+    fn from(sink: SinkConfig) -> Self {
+        Self {
+            sink,
+            route: Route::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NotifiersConfig {
+    sinks: Vec<SinkEntry>,
+}
+
+fn load_sink_configs() -> Result<Vec<SinkEntry>> {
+    if let Some(path) = notifiers_file() {
+        let raw = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?;
+        let config: NotifiersConfig =
+            serde_json::from_str(&raw).with_context(|| format!("Invalid notifiers JSON in {}", path))?;
+        return Ok(config.sinks);
+    }
+
+    let mut sinks = vec![SinkConfig::Console];
+    if let (Some(bot_token), Some(chat_id)) = (telegram_bot_token(), telegram_chat_id()) {
+        sinks.push(SinkConfig::Telegram {
+            name: None,
+            bot_token: Some(bot_token),
+            chat_id,
+        });
+    }
+    if let Some(webhook_url) = discord_webhook_url() {
+        sinks.push(SinkConfig::Discord { name: None, webhook_url });
+    }
+    if let Some(webhook_url) = slack_webhook_url() {
+        sinks.push(SinkConfig::Slack {
+            name: None,
+            webhook_url,
+            channel: None,
+            mentions: vec![],
+            mention_levels: default_mention_levels(),
+        });
+    }
+    if let Some(url) = webhook_url() {
+        sinks.push(SinkConfig::Webhook {
+            name: None,
+            url,
+            secret: webhook_secret(),
+            headers: HashMap::new(),
+            template: None,
+            template_file: None,
+            content_type: None,
+        });
+    }
+    if let (Some(host), Some(from)) = (smtp_host(), email_from()) {
+        sinks.push(SinkConfig::Email {
+            name: None,
+            host,
+            port: smtp_port(),
+            tls: smtp_tls().parse()?,
+            username: smtp_username(),
+            password: smtp_password(),
+            from,
+            to: email_to(),
+            batch_secs: email_batch_secs(),
+        });
+    }
+    Ok(sinks.into_iter().map(SinkEntry::from).collect())
+}
+
+fn build_sink(
+    config: SinkConfig,
+    client: &Client,
+    templates: &Arc<Templates>,
+    messages: &Arc<SentMessages>,
+) -> Result<Arc<dyn AlertSink>> {
+    let sink: Arc<dyn AlertSink> = match config {
+        SinkConfig::Console => Arc::new(ConsoleSink::new(templates.clone())),
+        SinkConfig::Telegram { name, bot_token, chat_id } => {
+            let bot_token = bot_token
+                .or_else(telegram_bot_token)
+                .context("Telegram sink needs bot_token or TELEGRAM_BOT_TOKEN")?;
+            Arc::new(TelegramSink::new(
+                client.clone(),
+                sink_name("telegram", name),
+                bot_token,
+                chat_id,
+                templates.clone(),
+                messages.clone(),
+            ))
+        }
+        SinkConfig::Discord { name, webhook_url } => Arc::new(DiscordSink::new(
+            client.clone(),
+            sink_name("discord", name),
+            webhook_url,
+            templates.clone(),
+            messages.clone(),
+        )?),
+        SinkConfig::Slack {
+            name,
+            webhook_url,
+            channel,
+            mentions,
+            mention_levels,
+        } => Arc::new(SlackSink::new(
+            client.clone(),
+            sink_name("slack", name),
+            webhook_url,
+            channel,
+            mentions,
+            mention_levels,
+            templates.clone(),
+        )),
+        SinkConfig::Webhook {
+            name,
+            url,
+            secret,
+            headers,
+            template,
+            template_file,
+            content_type,
+        } => {
+            let template = match (template, template_file) {
+                (Some(template), _) => Some(template),
+                (None, Some(path)) => {
+                    Some(std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?)
+                }
+                (None, None) => None,
+            };
+            Arc::new(WebhookSink::new(
+                client.clone(),
+                sink_name("webhook", name),
+                url,
+                secret,
+                headers,
+                template,
+                content_type,
+            )?)
+        }
+        SinkConfig::Email {
+            name,
+            host,
+            port,
+            tls,
+            username,
+            password,
+            from,
+            to,
+            batch_secs,
+        } => {
+            let settings = SmtpSettings {
+                host,
+                port,
+                tls,
+                username,
+                password,
+                from,
+                to,
+            };
+            let batch = (batch_secs > 0).then(|| Duration::from_secs(batch_secs));
+            Arc::new(EmailSink::new(sink_name("email", name), settings, batch, templates.clone())?)
+        }
+    };
+    Ok(sink)
+}
+
+fn sink_name(kind: &str, name: Option<String>) -> String {
+    match name {
+        Some(name) => format!("{}:{}", kind, name),
+        None => kind.to_string(),
+    }
+}
+
+// ============================================================================
+// DISPATCHER
+// ============================================================================
+
+pub struct Dispatcher {
+    sinks: Vec<Arc<dyn AlertSink>>,
+    /// Routing rule of each sink, by name
+    routes: HashMap<String, Route>,
+    queue: Arc<Mutex<RetryQueue>>,
+    /// Serializes queue saves (see `queue::persist`)
+    save_lock: Arc<tokio::sync::Mutex<()>>,
+    policy: RetryPolicy,
+    messages: Arc<SentMessages>,
+    /// Sinks that receive digests; empty means all
+    digest_sinks: Vec<String>,
+    /// Fan-outs still running, awaited by `drain` before exit
+    in_flight: Mutex<Vec<JoinHandle<Vec<DeliveryStatus>>>>,
+}
+
+impl Dispatcher {
+    /// Build every configured sink around one shared HTTP client
+    pub fn from_config() -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        let templates = Arc::new(Templates::load()?);
+        let messages = Arc::new(SentMessages::load());
+
+        let mut sinks = Vec::new();
+        let mut routes = HashMap::new();
+        for entry in load_sink_configs()? {
+            let sink = build_sink(entry.sink, &client, &templates, &messages)?;
+            if routes.insert(sink.name().to_string(), entry.route).is_some() {
+                bail!("Duplicate sink name '{}': give each sink a distinct name", sink.name());
+            }
+            sinks.push(sink);
+        }
+        let digest_sinks = digest_sinks();
+        if let Some(unknown) = digest_sinks.iter().find(|name| !routes.contains_key(*name)) {
+            bail!("DIGEST_SINKS names unknown sink '{}'", unknown);
+        }
+        Ok(Self {
+            sinks,
+            routes,
+            queue: Arc::new(Mutex::new(RetryQueue::load())),
+            save_lock: Arc::new(tokio::sync::Mutex::new(())),
+            policy: RetryPolicy::from_config(),
+            messages,
+            digest_sinks,
+            in_flight: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Sink names with their routing, e.g. "telegram:ops (HIGH; Politics)"
+    pub fn describe_sinks(&self) -> Vec<String> {
+        self.sinks
+            .iter()
+            .map(|s| match self.routes.get(s.name()).and_then(Route::describe) {
+                Some(route) => format!("{} ({})", s.name(), route),
+                None => s.name().to_string(),
+            })
+            .collect()
+    }
+
+    pub fn sink(&self, name: &str) -> Option<&Arc<dyn AlertSink>> {
+        self.sinks.iter().find(|s| s.name() == name)
+    }
+
+    /// Deliveries waiting for a retry
+    pub fn pending_retries(&self) -> usize {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner()).count()
+    }
+
+    /// Fan an alert out to every sink whose route matches, in the background.
+    /// Each remote delivery is queued before it is attempted (see `queue`).
+    /// The console sink prints immediately and is never queued; remote
+    /// failures are reported as they complete.
+    pub fn dispatch(&self, suspect: &SuspectTrade) {
+        let suspect = Arc::new(suspect.clone());
+        let (console, routed): (Vec<_>, Vec<_>) = self
+            .sinks
+            .iter()
+            .filter(|s| self.routes.get(s.name()).is_none_or(|r| r.matches(&suspect)))
+            .partition(|s| s.name() == console::NAME);
+        let names: Vec<&str> = routed.iter().map(|s| s.name()).collect();
+        let ids = self.queue.lock().unwrap_or_else(|e| e.into_inner()).enqueue(&names, &suspect);
+
+        let mut tasks = JoinSet::new();
+        for sink in console {
+            let sink = sink.clone();
+            let suspect = suspect.clone();
+            tasks.spawn(async move {
+                let started = Instant::now();
+                let result = sink.send(&suspect).await;
+                if let Err(e) = &result {
+                    eprintln!("{} Console alert failed: {:#}", "❌".red(), e);
+                }
+                DeliveryStatus {
+                    sink: sink.name().to_string(),
+                    error: result.err().map(|e| format!("{:#}", e)),
+                    elapsed: started.elapsed(),
+                    retry_in: None,
+                }
+            });
+        }
+        for (sink, id) in routed.into_iter().zip(ids) {
+            let sink = sink.clone();
+            let suspect = suspect.clone();
+            let queue = self.queue.clone();
+            let policy = self.policy;
+            tasks.spawn(async move {
+                let started = Instant::now();
+                let result = sink.send(&suspect).await;
+                let elapsed = started.elapsed();
+                let mut status = DeliveryStatus {
+                    sink: sink.name().to_string(),
+                    error: None,
+                    elapsed,
+                    retry_in: None,
+                };
+                let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+                match result {
+                    Ok(_) => queue.complete(id),
+                    Err(e) => {
+                        if let FailureOutcome::RetryIn(delay) = queue.record_failure(&policy, id, &e) {
+                            status.retry_in = Some(delay);
+                        }
+                        status.error = Some(format!("{:#}", e));
+                    }
+                }
+                status
+            });
+        }
+
+        self.track(spawn_report(tasks, true));
+    }
+
+    /// Report a grown position to the sinks its alert was routed to. Updates
+    /// are not retried: the next one carries the whole position anyway.
+    pub fn dispatch_update(&self, update: &AlertUpdate) {
+        self.messages.record_update(&update.suspect, chrono::Utc::now().timestamp());
+        let update = Arc::new(update.clone());
+        let mut tasks = JoinSet::new();
+        let routed = self.sinks.iter().filter(|s| {
+            s.sends_updates() && self.routes.get(s.name()).is_none_or(|r| r.matches(&update.suspect))
+        });
+        for sink in routed {
+            let sink = sink.clone();
+            let update = update.clone();
+            tasks.spawn(async move {
+                let started = Instant::now();
+                let result = sink.send_update(&update).await;
+                DeliveryStatus {
+                    sink: sink.name().to_string(),
+                    error: result.err().map(|e| format!("{:#}", e)),
+                    elapsed: started.elapsed(),
+                    retry_in: None,
+                }
+            });
+        }
+
+        self.track(spawn_report(tasks, false));
+    }
+
+    /// Edit the messages of alerts whose market resolved to show whether
+    /// they were right, keyed by alert ID. A message is forgotten once its
+    /// edit went through (or can never go through); failed edits are retried
+    /// on the next follow-up.
+    pub fn dispatch_resolutions(&self, outcomes: &HashMap<String, AlertOutcome>) {
+        for alert_id in self.messages.alert_ids() {
+            let Some(outcome) = outcomes.get(&alert_id).filter(|o| o.resolved) else {
+                continue;
+            };
+            let Some(sent) = self.messages.get(&alert_id) else {
+                continue;
+            };
+            let sent = Arc::new(sent);
+            let outcome = Arc::new(outcome.clone());
+            let mut tasks = JoinSet::new();
+            for sink in self.sinks.iter().filter(|s| sent.messages.contains_key(s.name())) {
+                let sink = sink.clone();
+                let sent = sent.clone();
+                let outcome = outcome.clone();
+                let messages = self.messages.clone();
+                let alert_id = alert_id.clone();
+                tasks.spawn(async move {
+                    let started = Instant::now();
+                    let result = sink.send_resolution(&sent, &outcome).await;
+                    let done = match &result {
+                        Ok(_) => true,
+                        Err(e) => is_permanent(e),
+                    };
+                    if done {
+                        messages.forget(&alert_id, sink.name());
+                    }
+                    DeliveryStatus {
+                        sink: sink.name().to_string(),
+                        error: result.err().map(|e| format!("{:#}", e)),
+                        elapsed: started.elapsed(),
+                        retry_in: None,
+                    }
+                });
+            }
+            self.track(spawn_report(tasks, false));
+        }
+    }
+
+    /// Sink names digests go to
+    pub fn describe_digest_sinks(&self) -> Vec<String> {
+        self.digest_targets().map(|s| s.name().to_string()).collect()
+    }
+
+    fn digest_targets(&self) -> impl Iterator<Item = &Arc<dyn AlertSink>> {
+        self.sinks
+            .iter()
+            .filter(|s| self.digest_sinks.is_empty() || self.digest_sinks.iter().any(|n| n == s.name()))
+    }
+
+    /// Send a digest to DIGEST_SINKS (every sink if unset). Routes don't
+    /// apply, and failed digests are not retried.
+    pub fn dispatch_digest(&self, digest: &Digest) {
+        let digest = Arc::new(digest.clone());
+        let mut tasks = JoinSet::new();
+        for sink in self.digest_targets() {
+            let sink = sink.clone();
+            let digest = digest.clone();
+            tasks.spawn(async move {
+                let started = Instant::now();
+                let result = sink.send_digest(&digest).await;
+                DeliveryStatus {
+                    sink: sink.name().to_string(),
+                    error: result.err().map(|e| format!("{:#}", e)),
+                    elapsed: started.elapsed(),
+                    retry_in: None,
+                }
+            });
+        }
+
+        self.track(spawn_report(tasks, false));
+    }
+
+    /// Keep a fan-out's handle for `drain`, forgetting finished ones
+    fn track(&self, handle: JoinHandle<Vec<DeliveryStatus>>) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        in_flight.retain(|h| !h.is_finished());
+        in_flight.push(handle);
+    }
+
+    /// Wait for deliveries still running, then save the queue (call before
+    /// exiting). Any that don't finish in time stay queued for the next run.
+    pub async fn drain(&self) {
+        let handles = std::mem::take(&mut *self.in_flight.lock().unwrap_or_else(|e| e.into_inner()));
+        let pending = handles.iter().filter(|h| !h.is_finished()).count();
+        if pending > 0 {
+            println!("{} Waiting for {} deliveries in flight...", "⏳".cyan(), pending);
+            let all = async {
+                for handle in handles {
+                    let _ = handle.await;
+                }
+            };
+            if tokio::time::timeout(DRAIN_TIMEOUT, all).await.is_err() {
+                eprintln!("{} Deliveries still running, left queued for the next run", "⚠️".yellow());
+            }
+        }
+        persist(&self.queue, &self.save_lock).await;
+    }
+
+    /// Background task saving the queue and retrying queued deliveries as
+    /// they come due
+    pub fn spawn_retry_worker(&self) -> JoinHandle<()> {
+        let sinks = self.sinks.clone();
+        let queue = self.queue.clone();
+        let save_lock = self.save_lock.clone();
+        let policy = self.policy;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                persist(&queue, &save_lock).await;
+                let due = queue
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .take_due(chrono::Utc::now().timestamp());
+                for delivery in due {
+                    retry_delivery(&sinks, &queue, &policy, delivery).await;
+                }
+            }
+        })
+    }
+
+    /// Deliver alerts held back by batching sinks (call before exiting)
+    pub async fn flush(&self) {
+        for sink in &self.sinks {
+            if let Err(e) = sink.flush().await {
+                eprintln!("{} Failed to flush {}: {:#}", "❌".red(), sink.name(), e);
+            }
+        }
+    }
+
+    /// Send each sink's startup test message, reporting the result
+    pub async fn send_tests(&self) {
+        for sink in self.sinks.iter().filter(|s| s.sends_test()) {
+            println!("{} Sending test message to {}...", "📱".cyan(), sink.name());
+            match sink.send_test().await {
+                Ok(_) => println!("{} {} test successful! Check your chat.\n", "✅".green(), sink.name()),
+                Err(e) => eprintln!("{} {} test failed: {:#}\n", "❌".red(), sink.name(), e),
+            }
+        }
+    }
+}
+
+/// Collect the statuses of a fan-out as they complete, then report them
+fn spawn_report(mut tasks: JoinSet<DeliveryStatus>, retried: bool) -> JoinHandle<Vec<DeliveryStatus>> {
+    tokio::spawn(async move {
+        let mut statuses = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok(status) = joined {
+                statuses.push(status);
+            }
+        }
+        report_statuses(&statuses, retried);
+        statuses
+    })
+}
+
+/// One status line per alert, listing only remote sinks. `retried` says
+/// whether failures went to the retry queue.
+fn report_statuses(statuses: &[DeliveryStatus], retried: bool) {
+    let remote: Vec<&DeliveryStatus> = statuses.iter().filter(|s| s.sink != console::NAME).collect();
+    if remote.is_empty() {
+        return;
+    }
+    let parts: Vec<String> = remote
+        .iter()
+        .map(|s| match &s.error {
+            None => format!("{} ✓ ({}ms)", s.sink, s.elapsed.as_millis()),
+            Some(e) => match s.retry_in {
+                Some(delay) => format!("{} ✗ ({}), retrying in {}s", s.sink, e, delay.as_secs()),
+                None if retried => format!("{} ✗ ({}), dead-lettered", s.sink, e),
+                None => format!("{} ✗ ({})", s.sink, e),
+            },
+        })
+        .collect();
+    if remote.iter().all(|s| s.delivered()) {
+        println!("{} Delivered: {}", "📨".green(), parts.join(" | "));
+    } else {
+        eprintln!("{} Delivery: {}", "❌".red(), parts.join(" | "));
+    }
+}
@@ -0,0 +1,188 @@
+//! Versioned JSON schema of an alert, for downstream consumers
+//!
+//! Field names are part of the contract: add fields freely, but rename or
+//! remove one only together with a bump of `SCHEMA_VERSION`.
+
+use serde::Serialize;
+
+use crate::dedup::AlertUpdate;
+use crate::types::{AlertLevel, Detector, SuspectTrade, Trade, TrackRecord, UserStats};
+
+pub const SCHEMA: &str = "polymarket-insider-tracker/alert";
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertPayload {
+    pub schema: &'static str,
+    pub schema_version: u32,
+    /// "alert" for a new alert, "update" when an alerted position grew
+    pub event: &'static str,
+    /// Same ID as in `alerts.jsonl`
+    pub id: String,
+    pub sent_at: i64,
+    pub level: AlertLevel,
+    pub detector: Detector,
+    pub detector_label: String,
+    pub reason: String,
+    pub bet: Option<String>,
+    pub score: ScorePayload,
+    pub position_value_usd: f64,
+    pub market: MarketPayload,
+    pub trade: TradePayload,
+    pub user_stats: UserStatsPayload,
+    /// Constituent fills of an aggregated position (empty for single trades)
+    pub fills: Vec<TradePayload>,
+    /// Every wallet in a cluster alert (empty for single-wallet alerts)
+    pub members: Vec<UserStatsPayload>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScorePayload {
+    pub total: f64,
+    pub components: Vec<ScoreComponentPayload>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreComponentPayload {
+    pub name: String,
+    pub points: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketPayload {
+    pub title: Option<String>,
+    pub condition_id: Option<String>,
+    pub event_slug: Option<String>,
+    pub category: Option<String>,
+    pub end_date: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradePayload {
+    pub wallet: String,
+    pub pseudonym: Option<String>,
+    pub side: String,
+    pub outcome: Option<String>,
+    pub outcome_index: Option<i32>,
+    pub asset: Option<String>,
+    pub price: f64,
+    pub size: f64,
+    pub value_usd: f64,
+    pub timestamp: i64,
+    pub transaction_hash: Option<String>,
+    pub tx_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserStatsPayload {
+    pub address: String,
+    pub unique_markets: usize,
+    pub total_trades: usize,
+    pub first_activity_timestamp: Option<i64>,
+    /// e.g. "$20000 from Binance (exchange) 3h before trade"
+    pub funding: Option<String>,
+    pub record: Option<TrackRecord>,
+}
+
+impl UserStatsPayload {
+    fn from_stats(stats: &UserStats, trade_ts: i64) -> Self {
+        Self {
+            address: stats.address.to_lowercase(),
+            unique_markets: stats.unique_markets,
+            total_trades: stats.total_trades,
+            first_activity_timestamp: stats.first_activity_timestamp,
+            funding: stats.funding.as_ref().map(|f| f.describe(trade_ts)),
+            record: stats.record.clone(),
+        }
+    }
+}
+
+impl TradePayload {
+    fn from_trade(trade: &Trade) -> Self {
+        Self {
+            wallet: trade.proxy_wallet.to_lowercase(),
+            pseudonym: trade.pseudonym.clone(),
+            side: trade.side.clone(),
+            outcome: trade.outcome.clone(),
+            outcome_index: trade.outcome_index,
+            asset: trade.asset.clone(),
+            price: trade.price,
+            size: trade.size,
+            value_usd: trade.value_usd(),
+            timestamp: trade.timestamp,
+            transaction_hash: trade.transaction_hash.clone(),
+            tx_url: trade.tx_url(),
+        }
+    }
+}
+
+impl AlertPayload {
+    pub fn from_suspect(suspect: &SuspectTrade) -> Self {
+        let trade = &suspect.trade;
+        Self {
+            schema: SCHEMA,
+            schema_version: SCHEMA_VERSION,
+            event: "alert",
+            id: suspect.alert_id(),
+            sent_at: chrono::Utc::now().timestamp(),
+            level: suspect.alert_level,
+            detector: suspect.detector,
+            detector_label: suspect.detector.to_string(),
+            reason: suspect.reason.clone(),
+            bet: suspect.bet.clone(),
+            score: ScorePayload {
+                total: suspect.score.total(),
+                components: suspect
+                    .score
+                    .components
+                    .iter()
+                    .map(|c| ScoreComponentPayload {
+                        name: c.name.clone(),
+                        points: c.points,
+                    })
+                    .collect(),
+            },
+            position_value_usd: suspect.position_value_usd(),
+            market: MarketPayload {
+                title: trade.title.clone(),
+                condition_id: trade.condition_id.clone(),
+                event_slug: trade.event_slug.clone(),
+                category: suspect.market.as_ref().and_then(|m| m.category.clone()),
+                end_date: suspect.market.as_ref().and_then(|m| m.end_date.clone()),
+                url: trade.market_url(),
+            },
+            trade: TradePayload::from_trade(trade),
+            user_stats: UserStatsPayload::from_stats(&suspect.user_stats, trade.timestamp),
+            fills: suspect.fills.iter().map(TradePayload::from_trade).collect(),
+            members: suspect
+                .members
+                .iter()
+                .map(|m| UserStatsPayload::from_stats(m, trade.timestamp))
+                .collect(),
+        }
+    }
+}
+
+/// An alerted position that grew: the alert as it stands now, same `id`
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdatePayload {
+    #[serde(flatten)]
+    pub alert: AlertPayload,
+    /// Position value in the previous alert or update
+    pub previous_value_usd: f64,
+    pub alerted_at: i64,
+}
+
+impl UpdatePayload {
+    pub fn from_update(update: &AlertUpdate) -> Self {
+        Self {
+            alert: AlertPayload {
+                event: "update",
+                ..AlertPayload::from_suspect(&update.suspect)
+            },
+            previous_value_usd: update.previous_value_usd,
+            alerted_at: update.alerted_at,
+        }
+    }
+}
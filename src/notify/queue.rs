@@ -0,0 +1,348 @@
+//! Durable delivery queue
+//!
+//! Every remote delivery is queued per sink before its first attempt and
+//! removed only once it succeeded, so an alert interrupted by a crash or
+//! Ctrl-C is sent on the next run (possibly twice, never not at all). Changes
+//! are written by the retry worker within a second, off the async runtime,
+//! and once more on exit (see `persist`). A failed delivery
+//! is retried with exponential backoff, or after the delay the service asked
+//! for when rate limited. The queue lives in `DATA_DIR/alert_queue.json`.
+//! After ALERT_MAX_ATTEMPTS, or at once for permanent errors (a 4xx other
+//! than 429, a broken template), the alert is appended to
+//! `DATA_DIR/dead_letters.jsonl`, where the `dead-letters replay` command can
+//! pick it up again.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use super::{is_permanent, AlertSink, Dispatcher, Permanent, RateLimited};
+use crate::config::{alert_max_attempts, alert_retry_base_secs, alert_retry_max_secs};
+use crate::storage::{append_jsonl, load_json, load_jsonl, save_json, save_jsonl};
+use crate::types::SuspectTrade;
+
+const QUEUE_FILE: &str = "alert_queue.json";
+const DEAD_LETTER_FILE: &str = "dead_letters.jsonl";
+
+/// An alert not yet delivered to one sink
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PendingDelivery {
+    /// Handle within this run, assigned on enqueue and load
+    #[serde(skip)]
+    pub id: u64,
+    pub sink: String,
+    /// Attempts made so far
+    pub attempts: u32,
+    /// Unix time of the next attempt
+    pub next_attempt_at: i64,
+    #[serde(default)]
+    pub last_error: String,
+    pub suspect: SuspectTrade,
+    /// Being attempted right now; entries loaded from disk never are
+    #[serde(skip)]
+    in_flight: bool,
+}
+
+/// An alert that could not be delivered to a sink
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeadLetter {
+    pub failed_at: i64,
+    pub sink: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub suspect: SuspectTrade,
+}
+
+/// What happened to a failed delivery
+pub enum FailureOutcome {
+    RetryIn(Duration),
+    DeadLettered,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base: Duration,
+    pub max: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config() -> Self {
+        Self {
+            max_attempts: alert_max_attempts().max(1),
+            base: Duration::from_secs(alert_retry_base_secs()),
+            max: Duration::from_secs(alert_retry_max_secs()),
+        }
+    }
+
+    /// Delay before the next attempt after `attempts` failures, unless the
+    /// service told us how long to wait
+    fn delay(&self, attempts: u32, error: &anyhow::Error) -> Duration {
+        if let Some(limited) = error.downcast_ref::<RateLimited>() {
+            return limited.retry_after;
+        }
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.base.saturating_mul(factor).min(self.max)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RetryQueue {
+    pending: Vec<PendingDelivery>,
+    next_id: u64,
+    /// Dead letters not yet appended to the file
+    dead: Vec<DeadLetter>,
+    /// Changed since the last save
+    dirty: bool,
+}
+
+impl RetryQueue {
+    /// Load deliveries left over from the last run, including interrupted ones
+    pub fn load() -> Self {
+        let mut queue = match load_json::<Vec<PendingDelivery>>(QUEUE_FILE) {
+            Ok(pending) => Self {
+                pending: pending.unwrap_or_default(),
+                ..Self::default()
+            },
+            Err(e) => {
+                eprintln!("{} Failed to load alert queue: {:#}", "⚠️".yellow(), e);
+                Self::default()
+            }
+        };
+        for delivery in &mut queue.pending {
+            delivery.id = queue.next_id;
+            queue.next_id += 1;
+        }
+        queue
+    }
+
+    pub fn count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Queue an alert for each sink before the first attempt; returns their IDs
+    pub fn enqueue(&mut self, sinks: &[&str], suspect: &SuspectTrade) -> Vec<u64> {
+        let now = chrono::Utc::now().timestamp();
+        let ids: Vec<u64> = sinks
+            .iter()
+            .map(|sink| {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.pending.push(PendingDelivery {
+                    id,
+                    sink: sink.to_string(),
+                    attempts: 0,
+                    next_attempt_at: now,
+                    last_error: String::new(),
+                    suspect: suspect.clone(),
+                    in_flight: true,
+                });
+                id
+            })
+            .collect();
+        if !ids.is_empty() {
+            self.dirty = true;
+        }
+        ids
+    }
+
+    /// Drop a delivery that succeeded
+    pub fn complete(&mut self, id: u64) {
+        let before = self.pending.len();
+        self.pending.retain(|p| p.id != id);
+        if self.pending.len() != before {
+            self.dirty = true;
+        }
+    }
+
+    /// Schedule a failed attempt's retry, or dead-letter the delivery when
+    /// out of attempts or the error is permanent
+    pub fn record_failure(&mut self, policy: &RetryPolicy, id: u64, error: &anyhow::Error) -> FailureOutcome {
+        let now = chrono::Utc::now().timestamp();
+        let Some(index) = self.pending.iter().position(|p| p.id == id) else {
+            return FailureOutcome::DeadLettered;
+        };
+        let delivery = &mut self.pending[index];
+        delivery.attempts += 1;
+        delivery.last_error = format!("{:#}", error);
+
+        if delivery.attempts >= policy.max_attempts || is_permanent(error) {
+            let delivery = self.pending.remove(index);
+            self.dead.push(DeadLetter {
+                failed_at: now,
+                sink: delivery.sink,
+                attempts: delivery.attempts,
+                last_error: delivery.last_error,
+                suspect: delivery.suspect,
+            });
+            self.dirty = true;
+            return FailureOutcome::DeadLettered;
+        }
+
+        let delay = policy.delay(delivery.attempts, error);
+        delivery.next_attempt_at = now + delay.as_secs_f64().ceil() as i64;
+        delivery.in_flight = false;
+        self.dirty = true;
+        FailureOutcome::RetryIn(delay)
+    }
+
+    /// Deliveries whose retry time has come, marked in flight. They stay
+    /// queued until `complete` or `record_failure`.
+    pub fn take_due(&mut self, now: i64) -> Vec<PendingDelivery> {
+        self.pending
+            .iter_mut()
+            .filter(|p| !p.in_flight && p.next_attempt_at <= now)
+            .map(|p| {
+                p.in_flight = true;
+                p.clone()
+            })
+            .collect()
+    }
+
+    /// What to write since the last save: new dead letters and the whole
+    /// queue, or `None` when nothing changed
+    fn take_changes(&mut self) -> Option<(Vec<DeadLetter>, Vec<PendingDelivery>)> {
+        if !self.dirty {
+            return None;
+        }
+        self.dirty = false;
+        Some((std::mem::take(&mut self.dead), self.pending.clone()))
+    }
+
+    /// Put back changes that failed to save, to retry on the next save
+    fn restore_changes(&mut self, mut dead: Vec<DeadLetter>) {
+        dead.append(&mut self.dead);
+        self.dead = dead;
+        self.dirty = true;
+    }
+}
+
+/// Write the queue's changes since the last save on the blocking pool, so
+/// neither the queue lock nor the async runtime waits for the disk.
+/// `save_lock` keeps an older snapshot from landing after a newer one.
+pub async fn persist(queue: &Mutex<RetryQueue>, save_lock: &tokio::sync::Mutex<()>) {
+    let _saving = save_lock.lock().await;
+    let Some((dead, pending)) = queue.lock().unwrap_or_else(|e| e.into_inner()).take_changes() else {
+        return;
+    };
+    let written = tokio::task::spawn_blocking(move || {
+        // Dead letters first: a crash in between duplicates rather than loses
+        for (index, letter) in dead.iter().enumerate() {
+            if let Err(e) = append_jsonl(DEAD_LETTER_FILE, letter) {
+                eprintln!("{} Failed to write dead letter: {:#}", "❌".red(), e);
+                return Err(dead.into_iter().skip(index).collect());
+            }
+        }
+        if let Err(e) = save_json(QUEUE_FILE, &pending) {
+            eprintln!("{} Failed to save alert queue: {:#}", "❌".red(), e);
+            return Err(Vec::new());
+        }
+        Ok(())
+    })
+    .await;
+    match written {
+        Ok(Ok(())) => {}
+        Ok(Err(unwritten)) => queue.lock().unwrap_or_else(|e| e.into_inner()).restore_changes(unwritten),
+        Err(e) => eprintln!("{} Alert queue save task failed: {}", "❌".red(), e),
+    }
+}
+
+/// Retry one queued delivery, rescheduling it on failure
+pub async fn retry_delivery(
+    sinks: &[Arc<dyn AlertSink>],
+    queue: &Mutex<RetryQueue>,
+    policy: &RetryPolicy,
+    delivery: PendingDelivery,
+) {
+    let attempts = delivery.attempts + 1;
+    let result = match sinks.iter().find(|s| s.name() == delivery.sink) {
+        Some(sink) => sink.send(&delivery.suspect).await,
+        None => Err(Permanent(format!("sink '{}' is no longer configured", delivery.sink)).into()),
+    };
+    let title = delivery.suspect.trade.title.as_deref().unwrap_or("Unknown Market");
+
+    let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+    let Err(error) = result else {
+        queue.complete(delivery.id);
+        println!("{} Delivered to {} on attempt {}: {}", "📨".green(), delivery.sink, attempts, title);
+        return;
+    };
+    match queue.record_failure(policy, delivery.id, &error) {
+        FailureOutcome::RetryIn(delay) => eprintln!(
+            "{} {} attempt {}/{} failed ({:#}), retrying in {}s",
+            "🔁".yellow(),
+            delivery.sink,
+            attempts,
+            policy.max_attempts,
+            error,
+            delay.as_secs()
+        ),
+        FailureOutcome::DeadLettered => eprintln!(
+            "{} Gave up on {} after {} attempts ({:#}): {}",
+            "💀".red(),
+            delivery.sink,
+            attempts,
+            error,
+            title
+        ),
+    }
+}
+
+// ============================================================================
+// DEAD-LETTER COMMAND
+// ============================================================================
+
+/// Run the `dead-letters` command: list undelivered alerts, or resend them
+pub async fn run_dead_letter_command(replay: bool) -> Result<()> {
+    let letters: Vec<DeadLetter> = load_jsonl(DEAD_LETTER_FILE)?;
+    if letters.is_empty() {
+        println!("{} No dead letters", "📭".cyan());
+        return Ok(());
+    }
+
+    if !replay {
+        println!("{} {} undelivered alerts:", "💀".cyan(), letters.len());
+        for letter in &letters {
+            let failed_at = chrono::DateTime::from_timestamp(letter.failed_at, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| letter.failed_at.to_string());
+            println!(
+                "  {}  {:<16} [{}] {} - {} attempts, last error: {}",
+                failed_at,
+                letter.sink,
+                letter.suspect.alert_level,
+                letter.suspect.trade.title.as_deref().unwrap_or("Unknown Market"),
+                letter.attempts,
+                letter.last_error
+            );
+        }
+        return Ok(());
+    }
+
+    let dispatcher = Dispatcher::from_config()?;
+    let mut remaining = Vec::new();
+    for mut letter in letters {
+        let result = match dispatcher.sink(&letter.sink) {
+            Some(sink) => sink.send(&letter.suspect).await,
+            None => Err(anyhow::anyhow!("sink '{}' is not configured", letter.sink)),
+        };
+        let title = letter.suspect.trade.title.as_deref().unwrap_or("Unknown Market").to_string();
+        match result {
+            Ok(_) => println!("{} {} → {}", "✅".green(), title, letter.sink),
+            Err(e) => {
+                eprintln!("{} {} → {}: {:#}", "❌".red(), title, letter.sink, e);
+                letter.attempts += 1;
+                letter.last_error = format!("{:#}", e);
+                remaining.push(letter);
+            }
+        }
+    }
+
+    dispatcher.flush().await;
+    save_jsonl(DEAD_LETTER_FILE, &remaining)?;
+    println!("{} {} still undelivered", "💀".cyan(), remaining.len());
+    Ok(())
+}
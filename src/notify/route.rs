@@ -0,0 +1,53 @@
+//! Per-sink routing rules
+//!
+//! Each sink in NOTIFIERS_FILE may carry a `route` that limits which alerts it
+//! receives, e.g. `{ "levels": ["HIGH"], "categories": ["Politics"] }`. Empty
+//! lists match everything; all given lists must match.
+
+use serde::Deserialize;
+
+use crate::types::{AlertLevel, Detector, SuspectTrade};
+
+/// Category label for alerts whose market has none (as in the report)
+const UNCATEGORIZED: &str = "Uncategorized";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Route {
+    #[serde(default)]
+    pub levels: Vec<AlertLevel>,
+    /// Market categories, case-insensitive
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub detectors: Vec<Detector>,
+}
+
+impl Route {
+    pub fn matches(&self, suspect: &SuspectTrade) -> bool {
+        let category = suspect
+            .market
+            .as_ref()
+            .and_then(|m| m.category.as_deref())
+            .unwrap_or(UNCATEGORIZED);
+
+        (self.levels.is_empty() || self.levels.contains(&suspect.alert_level))
+            && (self.categories.is_empty() || self.categories.iter().any(|c| c.eq_ignore_ascii_case(category)))
+            && (self.detectors.is_empty() || self.detectors.contains(&suspect.detector))
+    }
+
+    /// e.g. "HIGH; Politics, Business" (None when everything matches)
+    pub fn describe(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if !self.levels.is_empty() {
+            parts.push(self.levels.iter().map(|l| l.to_string()).collect::<Vec<_>>().join("/"));
+        }
+        if !self.categories.is_empty() {
+            parts.push(self.categories.join(", "));
+        }
+        if !self.detectors.is_empty() {
+            parts.push(self.detectors.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", "));
+        }
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+}
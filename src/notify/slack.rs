@@ -0,0 +1,121 @@
+//! Slack sink: Block Kit message via an incoming webhook
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+
+use super::templates::{AlertContext, DigestContext, Templates, UpdateContext};
+use super::{response_error, retry_after, AlertSink, RateLimited};
+use crate::dedup::AlertUpdate;
+use crate::digest::Digest;
+use crate::types::{AlertLevel, SuspectTrade};
+
+pub struct SlackSink {
+    client: Client,
+    name: String,
+    webhook_url: String,
+    /// Overrides the webhook's default channel, e.g. "#insider-alerts".
+    /// Legacy webhooks only; Slack-app webhooks ignore it.
+    channel: Option<String>,
+    /// Prepended to alerts of `mention_levels`, e.g. "<!here>" or "<@U123>"
+    mentions: Vec<String>,
+    mention_levels: Vec<AlertLevel>,
+    templates: Arc<Templates>,
+}
+
+impl SlackSink {
+    pub fn new(
+        client: Client,
+        name: String,
+        webhook_url: String,
+        channel: Option<String>,
+        mentions: Vec<String>,
+        mention_levels: Vec<AlertLevel>,
+        templates: Arc<Templates>,
+    ) -> Self {
+        Self {
+            client,
+            name,
+            webhook_url,
+            channel,
+            mentions,
+            mention_levels,
+            templates,
+        }
+    }
+
+    fn build_message(&self, suspect: &SuspectTrade) -> Result<Value> {
+        let mut message = self.templates.render_json("slack", &AlertContext::from_suspect(suspect))?;
+        if !self.mentions.is_empty() && self.mention_levels.contains(&suspect.alert_level) {
+            let text = message["text"].as_str().unwrap_or_default();
+            message["text"] = json!(format!("{} {}", self.mentions.join(" "), text));
+        }
+        if let Some(channel) = &self.channel {
+            message["channel"] = json!(channel);
+        }
+        Ok(message)
+    }
+
+    async fn post(&self, message: &Value) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited {
+                retry_after: retry_after(&response).unwrap_or(Duration::from_secs(1)),
+            }
+            .into());
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(response_error(status, format!("Slack webhook error: {}", error_text)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AlertSink for SlackSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()> {
+        self.post(&self.build_message(suspect)?).await
+    }
+
+    async fn send_digest(&self, digest: &Digest) -> Result<()> {
+        let mut message = self
+            .templates
+            .render_json("slack_digest", &DigestContext::new(digest))?;
+        if let Some(channel) = &self.channel {
+            message["channel"] = json!(channel);
+        }
+        self.post(&message).await
+    }
+
+    fn sends_updates(&self) -> bool {
+        true
+    }
+
+    /// Updates go to the same channel, without mentions
+    async fn send_update(&self, update: &AlertUpdate) -> Result<()> {
+        let mut message = self
+            .templates
+            .render_json("slack_update", &UpdateContext::from_update(update))?;
+        if let Some(channel) = &self.channel {
+            message["channel"] = json!(channel);
+        }
+        self.post(&message).await
+    }
+}
@@ -0,0 +1,198 @@
+//! Telegram sink: HTML message to one chat via the Bot API
+//!
+//! Sent alerts are edited in place as the position grows and once the market
+//! resolves (see `messages`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::messages::{SentAlert, SentMessages};
+use super::templates::{AlertContext, DigestContext, Templates, UpdateContext};
+use super::{response_error, AlertSink, RateLimited};
+use crate::dedup::AlertUpdate;
+use crate::digest::Digest;
+use crate::followup::AlertOutcome;
+use crate::types::SuspectTrade;
+
+pub struct TelegramSink {
+    client: Client,
+    name: String,
+    bot_token: String,
+    chat_id: String,
+    templates: Arc<Templates>,
+    messages: Arc<SentMessages>,
+}
+
+impl TelegramSink {
+    pub fn new(
+        client: Client,
+        name: String,
+        bot_token: String,
+        chat_id: String,
+        templates: Arc<Templates>,
+        messages: Arc<SentMessages>,
+    ) -> Self {
+        Self {
+            client,
+            name,
+            bot_token,
+            chat_id,
+            templates,
+            messages,
+        }
+    }
+
+    /// Call a Bot API method, returning its `result`
+    async fn call(&self, method: &str, payload: Value) -> Result<Value> {
+        let url = format!("https://api.telegram.org/bot{}/{}", self.bot_token, method);
+        // The URL carries the bot token: keep it out of logs and the queue
+        let response = self.client.post(&url).json(&payload).send().await.map_err(|e| e.without_url())?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            // 429s carry {"parameters": {"retry_after": <secs>}}
+            let retry_after = serde_json::from_str::<Value>(&error_text)
+                .ok()
+                .and_then(|body| body["parameters"]["retry_after"].as_u64());
+            if let Some(secs) = retry_after {
+                return Err(RateLimited {
+                    retry_after: Duration::from_secs(secs),
+                }
+                .into());
+            }
+            return Err(response_error(status, format!("Telegram API error: {}", error_text)));
+        }
+        let mut body: Value = response.json().await.map_err(|e| e.without_url())?;
+        Ok(body["result"].take())
+    }
+
+    /// Replace the text of a message sent earlier
+    async fn edit_message(&self, message_id: &str, text: String) -> Result<()> {
+        let result = self
+            .call(
+                "editMessageText",
+                json!({
+                    "chat_id": self.chat_id,
+                    "message_id": message_id.parse::<i64>()?,
+                    "text": text,
+                    "parse_mode": "HTML",
+                    "disable_web_page_preview": false
+                }),
+            )
+            .await;
+        match result {
+            // Nothing changed since the last edit
+            Err(e) if e.to_string().contains("message is not modified") => Ok(()),
+            other => other.map(|_| ()),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for TelegramSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()> {
+        let message = self
+            .call(
+                "sendMessage",
+                json!({
+                    "chat_id": self.chat_id,
+                    "text": self.templates.render("telegram", &AlertContext::from_suspect(suspect))?,
+                    "parse_mode": "HTML",
+                    "disable_web_page_preview": false
+                }),
+            )
+            .await?;
+        if let Some(message_id) = message["message_id"].as_i64() {
+            self.messages.record(&self.name, suspect, message_id.to_string());
+        }
+        Ok(())
+    }
+
+    async fn send_digest(&self, digest: &Digest) -> Result<()> {
+        self.call(
+            "sendMessage",
+            json!({
+                "chat_id": self.chat_id,
+                "text": self.templates.render("telegram_digest", &DigestContext::new(digest))?,
+                "parse_mode": "HTML",
+                "disable_web_page_preview": true
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn sends_updates(&self) -> bool {
+        true
+    }
+
+    /// Edits the original alert when we have its message, else posts an update
+    async fn send_update(&self, update: &AlertUpdate) -> Result<()> {
+        if let Some(message_id) = self.messages.message_id(&self.name, &update.suspect.alert_id()) {
+            let context = AlertContext::from_suspect(&update.suspect).with_update(chrono::Utc::now().timestamp());
+            return self.edit_message(&message_id, self.templates.render("telegram", &context)?).await;
+        }
+        self.call(
+            "sendMessage",
+            json!({
+                "chat_id": self.chat_id,
+                "text": self.templates.render("telegram_update", &UpdateContext::from_update(update))?,
+                "parse_mode": "HTML",
+                "disable_web_page_preview": true
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn send_resolution(&self, sent: &SentAlert, outcome: &AlertOutcome) -> Result<()> {
+        let Some(message_id) = sent.messages.get(&self.name) else {
+            return Ok(());
+        };
+        let mut context = AlertContext::from_suspect(&sent.suspect).with_resolution(outcome);
+        if let Some(updated_at) = sent.updated_at {
+            context = context.with_update(updated_at);
+        }
+        self.edit_message(message_id, self.templates.render("telegram", &context)?).await
+    }
+
+    fn sends_test(&self) -> bool {
+        true
+    }
+
+    async fn send_test(&self) -> Result<()> {
+        // Use HTML parse mode - much easier to work with than MarkdownV2
+        let message = r#"🎯 <b>Polymarket REAL Insider Tracker</b>
+
+✅ Bot connected!
+
+Monitoring for:
+• Fresh wallets (≤2 prior markets)
+• Large trades (&gt;$5,000)
+• Contrarian bets (&lt;35% odds)
+• Excluded: crypto up/down, sports, hourly
+
+Alerts will appear here for REAL insider activity."#;
+
+        self.call(
+            "sendMessage",
+            json!({
+                "chat_id": self.chat_id,
+                "text": message,
+                "parse_mode": "HTML"
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+}
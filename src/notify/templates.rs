@@ -0,0 +1,547 @@
+//! Alert message templates
+//!
+//! Every sink renders its message with handlebars from one shared
+//! `AlertContext`: the versioned alert payload plus display-ready strings.
+//! Defaults are built in (`src/notify/templates/*.hbs`); a file of the same
+//! name in TEMPLATES_DIR overrides one, so wording changes need no rebuild.
+//! Output is escaped for its destination: HTML for Telegram and email HTML,
+//! JSON strings for Discord and Slack, nothing for console and plain text.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use colored::*;
+use handlebars::{handlebars_helper, Handlebars};
+use serde::Serialize;
+use serde_json::Value as Json;
+
+use super::payload::AlertPayload;
+use super::Permanent;
+use crate::dedup::AlertUpdate;
+use crate::digest::{Digest, DigestAlert, ResolvedAlert};
+use crate::followup::AlertOutcome;
+use crate::api::mask_address;
+use crate::config::templates_dir;
+use crate::types::{AlertLevel, SuspectTrade};
+
+const MAX_FILLS_SHOWN: usize = 10;
+const MAX_DIGEST_ALERTS_SHOWN: usize = 10;
+const MAX_DIGEST_RESOLVED_SHOWN: usize = 10;
+
+/// How rendered values are escaped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    Html,
+    Json,
+    Plain,
+}
+
+/// Built-in templates: name, escaping, source
+const DEFAULTS: &[(&str, Escape, &str)] = &[
+    ("console", Escape::Plain, include_str!("templates/console.hbs")),
+    ("telegram", Escape::Html, include_str!("templates/telegram.hbs")),
+    ("discord", Escape::Json, include_str!("templates/discord.hbs")),
+    ("slack", Escape::Json, include_str!("templates/slack.hbs")),
+    ("email_subject", Escape::Plain, include_str!("templates/email_subject.hbs")),
+    ("email_text", Escape::Plain, include_str!("templates/email_text.hbs")),
+    ("email_html", Escape::Html, include_str!("templates/email_html.hbs")),
+    ("console_update", Escape::Plain, include_str!("templates/console_update.hbs")),
+    ("telegram_update", Escape::Html, include_str!("templates/telegram_update.hbs")),
+    ("discord_update", Escape::Json, include_str!("templates/discord_update.hbs")),
+    ("slack_update", Escape::Json, include_str!("templates/slack_update.hbs")),
+    ("console_digest", Escape::Plain, include_str!("templates/console_digest.hbs")),
+    ("telegram_digest", Escape::Html, include_str!("templates/telegram_digest.hbs")),
+    ("discord_digest", Escape::Json, include_str!("templates/discord_digest.hbs")),
+    ("slack_digest", Escape::Json, include_str!("templates/slack_digest.hbs")),
+    ("email_digest_subject", Escape::Plain, include_str!("templates/email_digest_subject.hbs")),
+    ("email_digest_text", Escape::Plain, include_str!("templates/email_digest_text.hbs")),
+    ("email_digest_html", Escape::Html, include_str!("templates/email_digest_html.hbs")),
+];
+
+// ============================================================================
+// CONTEXT
+// ============================================================================
+
+/// Everything a template can use about one alert
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertContext {
+    #[serde(flatten)]
+    pub alert: AlertPayload,
+    pub emoji: &'static str,
+    /// Console colour of the level, e.g. "red"
+    pub level_color: &'static str,
+    /// e.g. "#FF0000"
+    pub color_hex: &'static str,
+    /// Same colour as a number (Discord embeds)
+    pub color_int: u32,
+    pub divider: String,
+    pub market_title: String,
+    /// Outcome, falling back to the trade side
+    pub outcome_label: String,
+    /// Bet description, or "N/A"
+    pub bet_label: String,
+    /// Pseudonym, or "Anonymous"
+    pub pseudonym_label: String,
+    pub wallet_masked: String,
+    /// e.g. "$12345.67"
+    pub value: String,
+    /// e.g. "12.5%"
+    pub price_pct: String,
+    /// e.g. "72/100"
+    pub score_text: String,
+    /// e.g. "fresh 30 + size 20 + odds 12"
+    pub score_breakdown: String,
+    pub record_summary: Option<String>,
+    /// e.g. "2025-01-03 04:05:06 UTC"
+    pub time_utc: String,
+    /// e.g. "04:05:06 UTC"
+    pub time_short: String,
+    /// Transaction hash, or "N/A"
+    pub tx_label: String,
+    /// Most recent fills of an aggregated position
+    pub fills_shown: Vec<FillContext>,
+    /// Earlier fills left out of `fills_shown`
+    pub fills_skipped: usize,
+    /// Fills in the position (1 for a single trade)
+    pub fill_count: usize,
+    /// When the message was last edited for a grown position, e.g. "04:05:06 UTC"
+    pub updated_time: Option<String>,
+    /// Set once the market resolved
+    pub resolution: Option<ResolutionContext>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionContext {
+    pub won: bool,
+    /// "✅" or "❌"
+    pub emoji: &'static str,
+    /// "CORRECT" or "INCORRECT"
+    pub verdict: &'static str,
+    /// Winning outcome, or "N/A"
+    pub winner: String,
+    /// PnL of the alerted position, e.g. "+$4000.00"
+    pub pnl: String,
+    /// Green or red (Discord embeds)
+    pub color_int: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FillContext {
+    pub time: String,
+    pub wallet_masked: String,
+    /// Right-aligned, e.g. "$   1234.56"
+    pub value_padded: String,
+    pub price_pct: String,
+}
+
+/// Context of a position update: the alert as it stands now, plus the change
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateContext {
+    #[serde(flatten)]
+    pub alert: AlertContext,
+    pub previous_value_usd: f64,
+    pub alerted_at: i64,
+    /// e.g. "$12000.00"
+    pub previous_value: String,
+    /// e.g. "+$30000.00"
+    pub added_value: String,
+    /// When the original alert went out, e.g. "04:05:06 UTC"
+    pub alerted_time: String,
+}
+
+/// Context of a digest
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestContext {
+    #[serde(flatten)]
+    pub digest: Digest,
+    pub divider: String,
+    pub alert_count: usize,
+    /// Highest-scoring alerts, for chat messages
+    pub alerts_shown: Vec<DigestAlert>,
+    /// Alerts left out of `alerts_shown`
+    pub alerts_more: usize,
+    /// First resolved alerts, for chat messages
+    pub resolved_shown: Vec<ResolvedAlert>,
+    pub resolved_more: usize,
+}
+
+/// Context of an email: one or more alerts
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchContext {
+    pub alerts: Vec<AlertContext>,
+    /// The alert, when there is exactly one
+    pub single: Option<AlertContext>,
+    pub count: usize,
+    pub high: usize,
+}
+
+impl AlertContext {
+    pub fn from_suspect(suspect: &SuspectTrade) -> Self {
+        let trade = &suspect.trade;
+        let (emoji, level_color, color_hex, color_int) = match suspect.alert_level {
+            AlertLevel::High => ("🚨", "red", "#FF0000", 0xFF0000),
+            AlertLevel::Medium => ("⚠️", "yellow", "#FFA500", 0xFFA500),
+            AlertLevel::Low => ("📊", "cyan", "#00FF00", 0x00FF00),
+        };
+        let fills_skipped = suspect.fills.len().saturating_sub(MAX_FILLS_SHOWN);
+        let fills_shown = suspect
+            .fills
+            .iter()
+            .skip(fills_skipped)
+            .map(|fill| FillContext {
+                time: format_time(fill.timestamp, "%H:%M:%S"),
+                wallet_masked: mask_address(&fill.proxy_wallet),
+                value_padded: format!("${:>10.2}", fill.value_usd()),
+                price_pct: format!("{:.1}%", fill.price * 100.0),
+            })
+            .collect();
+
+        Self {
+            alert: AlertPayload::from_suspect(suspect),
+            emoji,
+            level_color,
+            color_hex,
+            color_int,
+            divider: "═".repeat(65),
+            market_title: trade.title.clone().unwrap_or_else(|| "Unknown Market".to_string()),
+            outcome_label: trade.outcome.clone().unwrap_or_else(|| trade.side.clone()),
+            bet_label: suspect.bet.clone().unwrap_or_else(|| "N/A".to_string()),
+            pseudonym_label: trade.pseudonym.clone().unwrap_or_else(|| "Anonymous".to_string()),
+            wallet_masked: mask_address(&suspect.user_stats.address),
+            value: format!("${:.2}", suspect.position_value_usd()),
+            price_pct: format!("{:.1}%", trade.price * 100.0),
+            score_text: format!("{:.0}/100", suspect.score.total()),
+            score_breakdown: suspect.score.breakdown(),
+            record_summary: suspect.user_stats.record.as_ref().map(|r| r.summary()),
+            time_utc: format_time(trade.timestamp, "%Y-%m-%d %H:%M:%S UTC"),
+            time_short: format_time(trade.timestamp, "%H:%M:%S UTC"),
+            tx_label: trade.transaction_hash.clone().unwrap_or_else(|| "N/A".to_string()),
+            fills_shown,
+            fills_skipped,
+            fill_count: suspect.fills.len().max(1),
+            updated_time: None,
+            resolution: None,
+        }
+    }
+
+    /// Mark the alert as edited for a grown position at `ts`
+    pub fn with_update(mut self, ts: i64) -> Self {
+        self.updated_time = Some(format_time(ts, "%H:%M:%S UTC"));
+        self
+    }
+
+    /// Mark the alert as correct or incorrect, once its market resolved
+    pub fn with_resolution(mut self, outcome: &AlertOutcome) -> Self {
+        let Some(won) = outcome.won else {
+            return self;
+        };
+        let (emoji, verdict, color_int) = if won {
+            ("✅", "CORRECT", 0x2ECC71)
+        } else {
+            ("❌", "INCORRECT", 0x95A5A6)
+        };
+        let pnl = outcome.pnl_usd.unwrap_or_default();
+        let sign = if pnl < 0.0 { "-" } else { "+" };
+        self.resolution = Some(ResolutionContext {
+            won,
+            emoji,
+            verdict,
+            winner: outcome.winner.clone().unwrap_or_else(|| "N/A".to_string()),
+            pnl: format!("{}${:.2}", sign, pnl.abs()),
+            color_int,
+        });
+        self
+    }
+}
+
+impl UpdateContext {
+    pub fn from_update(update: &AlertUpdate) -> Self {
+        let mut alert = AlertContext::from_suspect(&update.suspect);
+        alert.alert.event = "update";
+        let value = update.suspect.position_value_usd();
+        Self {
+            alert,
+            previous_value_usd: update.previous_value_usd,
+            alerted_at: update.alerted_at,
+            previous_value: format!("${:.2}", update.previous_value_usd),
+            added_value: format!("+${:.2}", value - update.previous_value_usd),
+            alerted_time: format_time(update.alerted_at, "%H:%M:%S UTC"),
+        }
+    }
+}
+
+impl DigestContext {
+    pub fn new(digest: &Digest) -> Self {
+        Self {
+            divider: "═".repeat(65),
+            alert_count: digest.alerts.len(),
+            alerts_shown: digest.alerts.iter().take(MAX_DIGEST_ALERTS_SHOWN).cloned().collect(),
+            alerts_more: digest.alerts.len().saturating_sub(MAX_DIGEST_ALERTS_SHOWN),
+            resolved_shown: digest.resolved.iter().take(MAX_DIGEST_RESOLVED_SHOWN).cloned().collect(),
+            resolved_more: digest.resolved.len().saturating_sub(MAX_DIGEST_RESOLVED_SHOWN),
+            digest: digest.clone(),
+        }
+    }
+}
+
+impl BatchContext {
+    pub fn new(suspects: &[SuspectTrade]) -> Self {
+        let alerts: Vec<AlertContext> = suspects.iter().map(AlertContext::from_suspect).collect();
+        Self {
+            single: (alerts.len() == 1).then(|| alerts[0].clone()),
+            count: alerts.len(),
+            high: suspects.iter().filter(|s| s.alert_level == AlertLevel::High).count(),
+            alerts,
+        }
+    }
+}
+
+fn format_time(ts: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format(format).to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+// ============================================================================
+// REGISTRY
+// ============================================================================
+
+/// Text of a template argument: strings as-is, anything else as JSON
+fn text(value: &Json) -> String {
+    match value {
+        Json::String(s) => s.clone(),
+        Json::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+handlebars_helper!(json: |value: Json| serde_json::to_string(value).unwrap_or_default());
+handlebars_helper!(bold: |value: Json| text(value).bold().to_string());
+handlebars_helper!(underline: |value: Json| text(value).underline().to_string());
+handlebars_helper!(color: |name: str, value: Json| text(value).color(name).to_string());
+handlebars_helper!(mrkdwn: |value: Json| text(value).replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"));
+handlebars_helper!(usd: |value: f64| if value < 0.0 { format!("-${:.2}", -value) } else { format!("${:.2}", value) });
+handlebars_helper!(pct: |value: f64| format!("{:.1}%", value * 100.0));
+handlebars_helper!(int: |value: f64| format!("{:.0}", value));
+handlebars_helper!(mask: |value: str| mask_address(value));
+
+/// Empty registry with the given escaping and the shared helpers
+fn registry(escape: Escape) -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    handlebars.register_helper("json", Box::new(json));
+    handlebars.register_helper("bold", Box::new(bold));
+    handlebars.register_helper("underline", Box::new(underline));
+    handlebars.register_helper("color", Box::new(color));
+    handlebars.register_helper("mrkdwn", Box::new(mrkdwn));
+    handlebars.register_helper("usd", Box::new(usd));
+    handlebars.register_helper("pct", Box::new(pct));
+    handlebars.register_helper("int", Box::new(int));
+    handlebars.register_helper("mask", Box::new(mask));
+    match escape {
+        Escape::Html => {}
+        Escape::Json => handlebars.register_escape_fn(|s| {
+            let quoted = serde_json::to_string(s).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        }),
+        Escape::Plain => handlebars.register_escape_fn(handlebars::no_escape),
+    }
+    handlebars
+}
+
+/// Compile a one-off template (e.g. a webhook payload) under `name`
+pub fn compile(name: &str, source: &str, escape: Escape) -> Result<Handlebars<'static>> {
+    let mut handlebars = registry(escape);
+    handlebars.register_template_string(name, source)?;
+    Ok(handlebars)
+}
+
+/// The message templates of every sink
+pub struct Templates {
+    registries: Vec<(Escape, Handlebars<'static>)>,
+}
+
+impl Templates {
+    /// Built-in templates, overridden by `<name>.hbs` files in TEMPLATES_DIR
+    pub fn load() -> Result<Self> {
+        let dir = templates_dir();
+        let mut registries: Vec<(Escape, Handlebars<'static>)> = [Escape::Html, Escape::Json, Escape::Plain]
+            .into_iter()
+            .map(|escape| (escape, registry(escape)))
+            .collect();
+
+        for (name, escape, default) in DEFAULTS {
+            let path = dir.as_deref().map(|d| Path::new(d).join(format!("{}.hbs", name)));
+            let source = match path.filter(|p| p.exists()) {
+                Some(path) => {
+                    std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+                }
+                None => default.to_string(),
+            };
+            let (_, handlebars) = registries
+                .iter_mut()
+                .find(|(e, _)| e == escape)
+                .expect("a registry per escape mode");
+            handlebars
+                .register_template_string(name, source)
+                .with_context(|| format!("Invalid template '{}'", name))?;
+        }
+        Ok(Self { registries })
+    }
+
+    pub fn render<T: Serialize>(&self, name: &str, data: &T) -> Result<String> {
+        let handlebars = self
+            .registries
+            .iter()
+            .map(|(_, h)| h)
+            .find(|h| h.has_template(name))
+            .with_context(|| format!("No template named '{}'", name))?;
+        // A broken template fails the same way on every retry
+        handlebars
+            .render(name, data)
+            .map_err(|e| Permanent(format!("Failed to render template '{}': {}", name, e)).into())
+    }
+
+    /// Render a JSON template (Discord embed, Slack blocks) into a value
+    pub fn render_json<T: Serialize>(&self, name: &str, data: &T) -> Result<Json> {
+        let rendered = self.render(name, data)?;
+        serde_json::from_str(&rendered)
+            .map_err(|e| Permanent(format!("Template '{}' did not produce valid JSON: {}", name, e)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{FunnelStats, MarketVolume, SCHEMA, SCHEMA_VERSION};
+    use crate::fixtures::{suspect, trade};
+    use crate::types::Detector;
+
+    const TITLE: &str = r#"Will "A&B" beat <C>?"#;
+
+    fn sample_suspect() -> SuspectTrade {
+        let mut first = trade("0x1234567890abcdef1234567890abcdef12345678", "BUY", 0.12, 50_000.0, 1_718_000_000);
+        first.title = Some(TITLE.to_string());
+        let mut second = first.clone();
+        second.timestamp += 60;
+        let mut suspect = suspect(first.clone(), AlertLevel::High, Detector::SplitOrder);
+        suspect.fills = vec![first, second];
+        suspect.bet = Some("Yes on a 12% long shot".to_string());
+        suspect.score.add("fresh", 30.0);
+        suspect
+    }
+
+    fn sample_digest() -> Digest {
+        Digest {
+            schema: SCHEMA,
+            schema_version: SCHEMA_VERSION,
+            event: "digest",
+            period_start: 1_718_000_000,
+            period_end: 1_718_003_600,
+            period_label: "2024-06-10 06:13 to 07:13 UTC".to_string(),
+            funnel: FunnelStats::default(),
+            alerts: vec![DigestAlert {
+                id: "alert-1".to_string(),
+                created_at: 1_718_000_000,
+                level: AlertLevel::High,
+                detector: Detector::FreshWallet,
+                score: 72.0,
+                wallet: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
+                market_title: TITLE.to_string(),
+                outcome: Some("Yes".to_string()),
+                value_usd: 6_000.0,
+                price: 0.12,
+                market_url: "https://polymarket.com/event/will-it-happen".to_string(),
+            }],
+            top_markets: vec![MarketVolume {
+                title: TITLE.to_string(),
+                url: "https://polymarket.com/event/will-it-happen".to_string(),
+                alerts: 1,
+                value_usd: 6_000.0,
+            }],
+            resolved: vec![ResolvedAlert {
+                id: "alert-0".to_string(),
+                level: AlertLevel::Medium,
+                market_title: TITLE.to_string(),
+                outcome: Some("No".to_string()),
+                won: true,
+                winner: Some("No".to_string()),
+                pnl_usd: 1_200.0,
+            }],
+            correct: 1,
+            incorrect: 0,
+            resolved_pnl_usd: 1_200.0,
+        }
+    }
+
+    fn resolved() -> AlertOutcome {
+        AlertOutcome {
+            resolved: true,
+            won: Some(true),
+            winner: Some("Yes".to_string()),
+            pnl_usd: Some(44_000.0),
+            ..AlertOutcome::default()
+        }
+    }
+
+    /// Render a default template from the context its sink uses
+    fn render_default(templates: &Templates, name: &str) -> Result<String> {
+        let suspect = sample_suspect();
+        if name.contains("digest") {
+            templates.render(name, &DigestContext::new(&sample_digest()))
+        } else if name.ends_with("_update") {
+            let update = AlertUpdate {
+                suspect: suspect.clone(),
+                previous_value_usd: 6_000.0,
+                alerted_at: suspect.trade.timestamp,
+            };
+            templates.render(name, &UpdateContext::from_update(&update))
+        } else if name.starts_with("email") {
+            templates.render(name, &BatchContext::new(&[suspect.clone(), suspect]))
+        } else {
+            templates.render(name, &AlertContext::from_suspect(&suspect))
+        }
+    }
+
+    #[test]
+    fn every_default_template_renders() {
+        let templates = Templates::load().unwrap();
+        for (name, escape, _) in DEFAULTS {
+            let rendered = render_default(&templates, name).unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+            assert!(!rendered.trim().is_empty(), "{} rendered empty", name);
+            if *escape == Escape::Json {
+                serde_json::from_str::<Json>(&rendered).unwrap_or_else(|e| panic!("{}: invalid JSON: {}", name, e));
+            }
+        }
+
+        // Edited messages render their update and resolution sections too
+        let context = AlertContext::from_suspect(&sample_suspect())
+            .with_update(1_718_000_600)
+            .with_resolution(&resolved());
+        for name in ["console", "telegram", "discord", "slack"] {
+            templates.render(name, &context).unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+        }
+    }
+
+    #[test]
+    fn market_title_is_escaped_for_each_destination() {
+        let templates = Templates::load().unwrap();
+        let context = AlertContext::from_suspect(&sample_suspect());
+
+        let telegram = templates.render("telegram", &context).unwrap();
+        assert!(telegram.contains("Will &quot;A&amp;B&quot; beat &lt;C&gt;?"), "{}", telegram);
+        assert!(!telegram.contains(TITLE));
+
+        let discord = templates.render_json("discord", &context).unwrap();
+        assert!(discord.to_string().contains(r#"Will \"A&B\" beat <C>?"#), "{}", discord);
+
+        // Slack mrkdwn additionally escapes its control characters
+        let slack = templates.render_json("slack", &context).unwrap();
+        assert!(slack.to_string().contains(r#"Will \"A&amp;B\" beat &lt;C&gt;?"#), "{}", slack);
+
+        // Webhook payload templates are JSON-escaped too
+        let webhook = compile("payload", r#"{"text": "{{market_title}}"}"#, Escape::Json).unwrap();
+        let body: Json = serde_json::from_str(&webhook.render("payload", &context).unwrap()).unwrap();
+        assert_eq!(body["text"], TITLE);
+    }
+}
@@ -0,0 +1,167 @@
+//! Generic webhook sink: POSTs the versioned alert JSON (see `payload`);
+//! position updates carry `"event": "update"` and the same `id`, digests
+//! `"event": "digest"`
+//!
+//! With a `secret`, each request is signed: `X-Signature-Timestamp` holds the
+//! Unix time and `X-Signature-256` is `sha256=<hex HMAC-SHA256 of
+//! "<timestamp>.<body>">`. A handlebars `template` (or `template_file`)
+//! replaces the body, rendered with the shared alert context (see `templates`).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use handlebars::Handlebars;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+
+use super::payload::{AlertPayload, UpdatePayload};
+use super::templates::{self, AlertContext, Escape, UpdateContext};
+use super::{response_error, retry_after, AlertSink, Permanent, RateLimited};
+use crate::dedup::AlertUpdate;
+use crate::digest::Digest;
+use crate::types::SuspectTrade;
+
+const TEMPLATE_NAME: &str = "payload";
+
+pub struct WebhookSink {
+    client: Client,
+    name: String,
+    url: String,
+    secret: Option<String>,
+    headers: HashMap<String, String>,
+    content_type: String,
+    template: Option<Handlebars<'static>>,
+}
+
+impl WebhookSink {
+    pub fn new(
+        client: Client,
+        name: String,
+        url: String,
+        secret: Option<String>,
+        headers: HashMap<String, String>,
+        template: Option<String>,
+        content_type: Option<String>,
+    ) -> Result<Self> {
+        let content_type = content_type.unwrap_or_else(|| "application/json".to_string());
+        let template = template
+            .map(|source| {
+                let escape = if content_type.contains("json") { Escape::Json } else { Escape::Plain };
+                templates::compile(TEMPLATE_NAME, &source, escape)
+            })
+            .transpose()
+            .with_context(|| format!("Invalid payload template for {}", name))?;
+        Ok(Self {
+            client,
+            name,
+            url,
+            secret,
+            headers,
+            content_type,
+            template,
+        })
+    }
+
+    fn render_body(&self, suspect: &SuspectTrade) -> Result<String> {
+        match &self.template {
+            Some(template) => render(template, &AlertContext::from_suspect(suspect)),
+            None => Ok(serde_json::to_string(&AlertPayload::from_suspect(suspect))?),
+        }
+    }
+
+    fn render_update(&self, update: &AlertUpdate) -> Result<String> {
+        match &self.template {
+            Some(template) => render(template, &UpdateContext::from_update(update)),
+            None => Ok(serde_json::to_string(&UpdatePayload::from_update(update))?),
+        }
+    }
+
+    async fn post(&self, body: String, content_type: &str) -> Result<()> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, content_type);
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        if let Some(secret) = &self.secret {
+            let timestamp = chrono::Utc::now().timestamp();
+            request = request
+                .header("X-Signature-Timestamp", timestamp.to_string())
+                .header("X-Signature-256", sign(secret, timestamp, &body)?);
+        }
+
+        let response = request.body(body).send().await.map_err(|e| e.without_url())?;
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited {
+                retry_after: retry_after(&response).unwrap_or(Duration::from_secs(1)),
+            }
+            .into());
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(response_error(status, format!("Webhook returned {}: {}", status, error_text)));
+        }
+        Ok(())
+    }
+}
+
+/// A broken template fails the same way on every retry
+fn render<T: Serialize>(template: &Handlebars<'static>, data: &T) -> Result<String> {
+    template
+        .render(TEMPLATE_NAME, data)
+        .map_err(|e| Permanent(format!("Failed to render webhook template: {}", e)).into())
+}
+
+/// `sha256=<hex>` signature of `<timestamp>.<body>`
+fn sign(secret: &str, timestamp: i64, body: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| anyhow!("Invalid HMAC key: {}", e))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    Ok(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, suspect: &SuspectTrade) -> Result<()> {
+        self.post(self.render_body(suspect)?, &self.content_type).await
+    }
+
+    /// Digests are always the digest JSON, whatever the alert template
+    async fn send_digest(&self, digest: &Digest) -> Result<()> {
+        self.post(serde_json::to_string(digest)?, "application/json").await
+    }
+
+    fn sends_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_update(&self, update: &AlertUpdate) -> Result<()> {
+        self.post(self.render_update(update)?, &self.content_type).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_is_hmac_of_timestamp_and_body() {
+        // Receivers verify this exact value, so it must never change
+        let signature = sign("whsec_test", 1718000000, r#"{"id":"abc"}"#).unwrap();
+        assert_eq!(
+            signature,
+            "sha256=6480eeafa8fbe650eca914306858d41066a98e4d7782328f423520aa8e88fe68"
+        );
+    }
+}
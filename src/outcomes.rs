@@ -0,0 +1,170 @@
+//! Outcome context: what a trade is actually betting on
+//!
+//! A raw price says little on its own. Buying NO at 30% is a bet against a
+//! 70% favourite, while buying one of twelve candidates at 30% in a negRisk
+//! event may be backing the favourite. This resolves the position a trade
+//! takes against its market and sibling markets.
+
+use crate::types::{Event, Market, Trade};
+
+/// The position a trade takes, in event context
+pub struct BetContext {
+    /// Probability the market assigns to the side the trader took
+    pub implied_prob: f64,
+    /// The trader backed the side the market favours
+    pub is_favourite: bool,
+    /// e.g. "NO on 'Will X happen?' (YES favoured at 70%)"
+    pub description: String,
+}
+
+impl BetContext {
+    /// Contrarian: a long shot that isn't the event's favourite
+    pub fn is_contrarian(&self, max_price: f64) -> bool {
+        self.implied_prob < max_price && !self.is_favourite
+    }
+}
+
+/// Resolve what `trade` is betting on. Sibling markets from `event` are used
+/// when the market belongs to a negRisk group.
+pub fn bet_context(trade: &Trade, market: &Market, event: Option<&Event>) -> BetContext {
+    let labels = market.outcome_labels();
+    let index = outcome_index(trade, &labels);
+
+    // Buying an outcome backs it; selling it backs the other side
+    let backs_listed = !trade.is_taker_sell();
+
+    match event.filter(|e| is_grouped(market, e)) {
+        Some(event) => {
+            // negRisk markets list YES first
+            let backs_yes = (index == 0) == backs_listed;
+            grouped_context(trade, market, event, backs_yes)
+        }
+        None => binary_context(trade, market, &labels, index, backs_listed),
+    }
+}
+
+fn is_grouped(market: &Market, event: &Event) -> bool {
+    market.neg_risk.unwrap_or(false) && event.markets.len() > 1
+}
+
+fn outcome_index(trade: &Trade, labels: &[String]) -> usize {
+    if let Some(i) = trade.outcome_index {
+        return i.max(0) as usize;
+    }
+    trade
+        .outcome
+        .as_deref()
+        .and_then(|o| labels.iter().position(|l| l.eq_ignore_ascii_case(o)))
+        .unwrap_or(0)
+}
+
+/// Two-outcome market: the bet is simply the side taken
+fn binary_context(
+    trade: &Trade,
+    market: &Market,
+    labels: &[String],
+    index: usize,
+    backs_listed: bool,
+) -> BetContext {
+    let label = |i: usize| {
+        labels
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| if i == 0 { "YES".to_string() } else { "NO".to_string() })
+    };
+    let (backed, other) = if backs_listed {
+        (label(index), label(1 - index.min(1)))
+    } else {
+        (label(1 - index.min(1)), label(index))
+    };
+
+    let implied_prob = trade.effective_price();
+    let is_favourite = implied_prob >= 0.5;
+    let question = market.question.as_deref().unwrap_or("Unknown Market");
+
+    let description = if is_favourite {
+        format!(
+            "{} on '{}' ({} favoured at {:.0}%)",
+            backed.to_uppercase(),
+            question,
+            backed.to_uppercase(),
+            implied_prob * 100.0
+        )
+    } else {
+        format!(
+            "{} on '{}' ({} favoured at {:.0}%)",
+            backed.to_uppercase(),
+            question,
+            other.to_uppercase(),
+            (1.0 - implied_prob) * 100.0
+        )
+    };
+
+    BetContext {
+        implied_prob,
+        is_favourite,
+        description,
+    }
+}
+
+/// negRisk group: the candidate's chance is its YES price relative to all siblings
+fn grouped_context(trade: &Trade, market: &Market, event: &Event, backs_yes: bool) -> BetContext {
+    // Traded price of this candidate's YES, siblings at their current YES price
+    let traded_yes = if backs_yes {
+        trade.effective_price()
+    } else {
+        1.0 - trade.effective_price()
+    };
+
+    let mut field: Vec<(String, f64)> = event
+        .markets
+        .iter()
+        .filter(|m| m.condition_id != market.condition_id)
+        .filter_map(|m| {
+            let yes = *m.outcome_price_list().first()?;
+            Some((m.display_name().to_string(), yes))
+        })
+        .collect();
+    let candidate = market.display_name().to_string();
+    field.push((candidate.clone(), traded_yes));
+
+    let total: f64 = field.iter().map(|(_, p)| p).sum();
+    let share = if total > 0.0 { traded_yes / total } else { traded_yes };
+
+    field.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let rank = field.iter().position(|(name, _)| *name == candidate).unwrap_or(0) + 1;
+    let (favourite, favourite_yes) = field[0].clone();
+    let favourite_share = if total > 0.0 { favourite_yes / total } else { favourite_yes };
+
+    let event_title = event.title.as_deref().unwrap_or("Unknown Event");
+    let n = field.len();
+
+    if backs_yes {
+        let description = if rank == 1 {
+            format!(
+                "YES on '{}' in '{}' (favourite of {}, {:.0}% implied)",
+                candidate, event_title, n, share * 100.0
+            )
+        } else {
+            format!(
+                "YES on '{}' in '{}' (#{} of {}, {:.0}% implied; favourite '{}' at {:.0}%)",
+                candidate, event_title, rank, n, share * 100.0, favourite, favourite_share * 100.0
+            )
+        };
+        BetContext {
+            implied_prob: share,
+            is_favourite: rank == 1,
+            description,
+        }
+    } else {
+        let implied_prob = 1.0 - share;
+        BetContext {
+            implied_prob,
+            is_favourite: implied_prob >= 0.5,
+            description: format!(
+                "NO on '{}' in '{}' (#{} of {}, {:.0}% implied to win)",
+                candidate, event_title, rank, n, share * 100.0
+            ),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! `wallet <address>` command: a wallet's complete computed profile
+//!
+//! Everything we'd otherwise look up in the browser when an alert fires:
+//! stats, every trade by market, open positions, PnL, funding, linked
+//! wallets and any prior alerts from local storage.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+
+use crate::alerts::{alerts_for_wallet, AlertRecord};
+use crate::api::{mask_address, ApiClient};
+use crate::chain::{classify_funding, load_address_labels, provider_from_config};
+use crate::config::max_price_threshold;
+use crate::graph::{WalletGraph, WalletLink};
+use crate::record::{calculate_track_record, traded_condition_ids};
+use crate::types::{AlertLevel, Market, Position, UserStats};
+use crate::watchlist::Watchlist;
+
+#[derive(Serialize)]
+pub struct WalletProfile {
+    pub address: String,
+    pub stats: UserStats,
+    pub last_activity_timestamp: Option<i64>,
+    pub flagged_level: Option<AlertLevel>,
+    pub watched: bool,
+    /// The funding lookup was configured but failed
+    pub funding_unavailable: bool,
+    pub trades: Vec<ProfileTrade>,
+    pub positions: Vec<Position>,
+    pub linked_wallets: Vec<WalletLink>,
+    pub alerts: Vec<AlertRecord>,
+}
+
+#[derive(Serialize)]
+pub struct ProfileTrade {
+    pub timestamp: Option<i64>,
+    pub condition_id: Option<String>,
+    pub title: Option<String>,
+    pub outcome: Option<String>,
+    pub side: String,
+    pub size: f64,
+    pub price: f64,
+    pub value_usd: f64,
+}
+
+/// Gather everything known about a wallet
+pub async fn build_profile(client: &mut ApiClient, address: &str) -> Result<WalletProfile> {
+    let activities = client.fetch_user_activity(address).await?;
+    let mut stats = ApiClient::calculate_user_stats(address, &activities);
+
+    // Full track record, no market cap: the user asked for this wallet
+    let mut markets: HashMap<String, Market> = HashMap::new();
+    for chunk in traded_condition_ids(&activities).chunks(20) {
+        for market in client.fetch_markets(chunk).await.unwrap_or_default() {
+            if let Some(cid) = market.condition_id.clone() {
+                markets.insert(cid, market);
+            }
+        }
+    }
+    stats.record = Some(calculate_track_record(&activities, &markets, max_price_threshold()));
+
+    let graph = WalletGraph::load();
+    let mut funding_unavailable = false;
+    if let Some(chain) = provider_from_config() {
+        // The rest of the profile is still useful without funding
+        match chain.first_funding(address).await {
+            Ok(Some(transfer)) => {
+                let source_flagged = graph.flagged_level(&transfer.from).is_some();
+                stats.funding = Some(classify_funding(transfer, &load_address_labels(), source_flagged));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("{} Funding lookup failed: {:#}", "⚠️".yellow(), e);
+                funding_unavailable = true;
+            }
+        }
+    }
+
+    let trades = activities
+        .iter()
+        .filter_map(|a| {
+            let side = a.side.clone()?;
+            let size = a.size.unwrap_or(0.0);
+            let price = a.price.unwrap_or(0.0);
+            Some(ProfileTrade {
+                timestamp: a.timestamp,
+                condition_id: a.condition_id.clone(),
+                title: a.title.clone(),
+                outcome: a.outcome.clone(),
+                side,
+                size,
+                price,
+                value_usd: a.usdc_size.unwrap_or(size * price),
+            })
+        })
+        .collect();
+
+    Ok(WalletProfile {
+        address: address.to_string(),
+        last_activity_timestamp: activities.iter().filter_map(|a| a.timestamp).max(),
+        flagged_level: graph.flagged_level(address),
+        watched: Watchlist::load().contains(address),
+        funding_unavailable,
+        trades,
+        positions: client.fetch_positions(address).await.unwrap_or_default(),
+        linked_wallets: graph.links(address).to_vec(),
+        alerts: alerts_for_wallet(address)?,
+        stats,
+    })
+}
+
+/// Run the `wallet` command
+pub async fn run_wallet_command(address: &str, json: bool) -> Result<()> {
+    let mut client = ApiClient::new();
+    let profile = build_profile(&mut client, address).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&profile)?);
+    } else {
+        print_profile(&profile);
+    }
+    Ok(())
+}
+
+fn format_ts(ts: Option<i64>) -> String {
+    ts.and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+fn print_profile(profile: &WalletProfile) {
+    let divider = "═".repeat(65);
+    let stats = &profile.stats;
+
+    println!();
+    println!("{}", divider.bright_white());
+    println!("👛 {} {}", "WALLET PROFILE".bold(), profile.address.cyan());
+    println!("{}", divider.bright_white());
+    println!("📚 History:   {} markets, {} trades", stats.unique_markets, stats.total_trades);
+    println!("📅 First:     {}", format_ts(stats.first_activity_timestamp));
+    println!("📅 Last:      {}", format_ts(profile.last_activity_timestamp));
+    if let Some(record) = &stats.record {
+        println!("🏆 Record:    {}", record.summary());
+    }
+    let open_pnl: f64 = profile.positions.iter().map(|p| p.cash_pnl).sum();
+    println!("💵 Open PnL:  {}${:.2}", if open_pnl >= 0.0 { "+" } else { "-" }, open_pnl.abs());
+    if let Some(funding) = &stats.funding {
+        let first_trade = stats.first_activity_timestamp.unwrap_or(funding.transfer.timestamp);
+        println!("🏦 Funding:   {}", funding.describe(first_trade));
+    } else if profile.funding_unavailable {
+        println!("🏦 Funding:   N/A (lookup failed)");
+    }
+    match profile.flagged_level {
+        Some(level) => println!("🚩 Flagged:   {}", level.to_string().red().bold()),
+        None => println!("🚩 Flagged:   no"),
+    }
+    println!("👀 Watched:   {}", if profile.watched { "yes" } else { "no" });
+
+    // Trades grouped by market, oldest first
+    let mut by_market: BTreeMap<String, Vec<&ProfileTrade>> = BTreeMap::new();
+    for trade in &profile.trades {
+        let title = trade.title.clone().unwrap_or_else(|| "Unknown Market".to_string());
+        by_market.entry(title).or_default().push(trade);
+    }
+    println!();
+    println!("{} ({})", "📈 Markets traded".bold(), by_market.len());
+    for (title, trades) in &by_market {
+        println!("  {}", title.white().bold());
+        for trade in trades.iter().rev() {
+            println!(
+                "     {}  {:<4} {:>10.2} × {:>5.1}%  ${:>10.2}  {}",
+                format_ts(trade.timestamp),
+                trade.side,
+                trade.size,
+                trade.price * 100.0,
+                trade.value_usd,
+                trade.outcome.as_deref().unwrap_or("")
+            );
+        }
+    }
+
+    println!();
+    println!("{} ({})", "💼 Open positions".bold(), profile.positions.len());
+    for position in &profile.positions {
+        let pnl = format!("{:+.2}", position.cash_pnl);
+        println!(
+            "  {} [{}] {:.2} sh @ {:.1}% → {:.1}%  value ${:.2}  PnL {}",
+            position.title.as_deref().unwrap_or("Unknown Market"),
+            position.outcome.as_deref().unwrap_or("?"),
+            position.size,
+            position.avg_price * 100.0,
+            position.cur_price * 100.0,
+            position.current_value,
+            if position.cash_pnl >= 0.0 { pnl.green() } else { pnl.red() }
+        );
+    }
+
+    println!();
+    println!("{} ({})", "🔗 Linked wallets".bold(), profile.linked_wallets.len());
+    for link in &profile.linked_wallets {
+        println!("  {}  {}  since {}", mask_address(&link.wallet), link.reason, format_ts(Some(link.since)));
+    }
+
+    println!();
+    println!("{} ({})", "🚨 Prior alerts".bold(), profile.alerts.len());
+    for alert in &profile.alerts {
+        println!(
+            "  {}  [{}] {}  {}  ${:.0}  score {:.0}",
+            format_ts(Some(alert.created_at)),
+            alert.alert_level,
+            alert.detector,
+            alert.market_title.as_deref().unwrap_or("Unknown Market"),
+            alert.value_usd,
+            alert.score
+        );
+    }
+    println!("{}", divider.bright_white());
+    println!();
+}
@@ -0,0 +1,119 @@
+//! Wallet track record: PnL and win rate on resolved markets
+//!
+//! A low-volume wallet that keeps hitting sub-35% outcomes is suspicious even
+//! if it isn't brand new. Positions are rebuilt from `/activity` trades and
+//! scored against market resolutions from the Gamma API.
+
+use std::collections::HashMap;
+
+use crate::types::{Market, TrackRecord, UserActivity};
+
+#[derive(Default)]
+struct Position {
+    bought_shares: f64,
+    cost: f64,
+    sold_shares: f64,
+    proceeds: f64,
+}
+
+/// Build a wallet's track record. `markets` maps condition ID to metadata;
+/// markets missing from it (or unresolved) only contribute realized sells.
+pub fn calculate_track_record(
+    activities: &[UserActivity],
+    markets: &HashMap<String, Market>,
+    longshot_price: f64,
+) -> TrackRecord {
+    let mut positions: HashMap<(String, usize), Position> = HashMap::new();
+
+    for activity in activities {
+        if activity.activity_type.as_deref().is_some_and(|t| !t.eq_ignore_ascii_case("TRADE")) {
+            continue;
+        }
+        let (Some(side), Some(cid), Some(size)) = (&activity.side, &activity.condition_id, activity.size) else {
+            continue;
+        };
+        let Some(index) = activity_outcome_index(activity, markets.get(cid)) else {
+            continue;
+        };
+        let value = activity
+            .usdc_size
+            .unwrap_or_else(|| size * activity.price.unwrap_or(0.0));
+
+        let position = positions.entry((cid.clone(), index)).or_default();
+        if side.eq_ignore_ascii_case("BUY") {
+            position.bought_shares += size;
+            position.cost += value;
+        } else {
+            position.sold_shares += size;
+            position.proceeds += value;
+        }
+    }
+
+    let mut record = TrackRecord::default();
+    let (mut winning_entries, mut losing_entries) = (Vec::new(), Vec::new());
+
+    for ((cid, index), position) in &positions {
+        if position.bought_shares <= 0.0 {
+            continue;
+        }
+        let avg_entry = position.cost / position.bought_shares;
+
+        match markets.get(cid).and_then(|m| m.winning_outcome_index()) {
+            Some(winner) => {
+                let won = *index == winner;
+                let held = (position.bought_shares - position.sold_shares).max(0.0);
+                let payout = if won { held } else { 0.0 };
+
+                record.realized_pnl += position.proceeds + payout - position.cost;
+                record.resolved_positions += 1;
+                if avg_entry < longshot_price {
+                    record.longshot_bets += 1;
+                }
+                if won {
+                    record.wins += 1;
+                    winning_entries.push(avg_entry);
+                    if avg_entry < longshot_price {
+                        record.longshot_wins += 1;
+                    }
+                } else {
+                    losing_entries.push(avg_entry);
+                }
+            }
+            None => {
+                // Open market: only what was sold is realized
+                record.realized_pnl += position.proceeds - avg_entry * position.sold_shares;
+            }
+        }
+    }
+
+    record.avg_entry_winning = average(&winning_entries);
+    record.avg_entry_losing = average(&losing_entries);
+    record
+}
+
+/// Distinct condition IDs a wallet has traded
+pub fn traded_condition_ids(activities: &[UserActivity]) -> Vec<String> {
+    let mut ids: Vec<String> = activities
+        .iter()
+        .filter(|a| a.side.is_some())
+        .filter_map(|a| a.condition_id.clone())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+fn activity_outcome_index(activity: &UserActivity, market: Option<&Market>) -> Option<usize> {
+    if let Some(i) = activity.outcome_index {
+        return Some(i.max(0) as usize);
+    }
+    let outcome = activity.outcome.as_deref()?;
+    market?
+        .outcome_labels()
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(outcome))
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
@@ -0,0 +1,194 @@
+//! `report` command: how good are our alerts?
+//!
+//! Precision (share of resolved alerts whose bet won), average return and
+//! PnL of the alerted positions, and the average price move after the
+//! alert, overall and broken down by detector, market category and level.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+
+use crate::alerts::{load_alerts, AlertRecord};
+use crate::api::ApiClient;
+use crate::followup::{load_outcomes, price_move, update_outcomes, AlertOutcome, PRICE_HORIZONS_HOURS};
+
+/// Aggregate results for a group of alerts
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AlertStats {
+    pub alerts: usize,
+    pub resolved: usize,
+    pub wins: usize,
+    /// Wins / resolved
+    pub precision: Option<f64>,
+    /// Mean return of resolved positions (1.0 = +100%)
+    pub avg_return: Option<f64>,
+    pub pnl_usd: f64,
+    /// Mean move in the bet's favour N hours after the alert, keyed by N
+    pub avg_price_move: BTreeMap<i64, f64>,
+
+    #[serde(skip)]
+    return_sum: f64,
+    #[serde(skip)]
+    moves: BTreeMap<i64, (f64, usize)>,
+}
+
+impl AlertStats {
+    fn add(&mut self, alert: &AlertRecord, outcome: Option<&AlertOutcome>) {
+        self.alerts += 1;
+        let Some(outcome) = outcome else {
+            return;
+        };
+        for hours in PRICE_HORIZONS_HOURS {
+            if let Some(change) = price_move(alert, outcome, hours) {
+                let entry = self.moves.entry(hours).or_default();
+                entry.0 += change;
+                entry.1 += 1;
+            }
+        }
+        if let Some(won) = outcome.won {
+            self.resolved += 1;
+            self.wins += usize::from(won);
+            self.pnl_usd += outcome.pnl_usd.unwrap_or(0.0);
+            self.return_sum += outcome.return_pct.unwrap_or(0.0);
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.resolved > 0 {
+            self.precision = Some(self.wins as f64 / self.resolved as f64);
+            self.avg_return = Some(self.return_sum / self.resolved as f64);
+        }
+        self.avg_price_move = self
+            .moves
+            .iter()
+            .map(|(hours, (sum, count))| (*hours, sum / *count as f64))
+            .collect();
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub overall: AlertStats,
+    pub by_detector: BTreeMap<String, AlertStats>,
+    pub by_category: BTreeMap<String, AlertStats>,
+    pub by_level: BTreeMap<String, AlertStats>,
+}
+
+/// Aggregate alerts with their follow-ups
+pub fn build_report(alerts: &[AlertRecord], outcomes: &HashMap<String, AlertOutcome>) -> Report {
+    let mut report = Report::default();
+    for alert in alerts {
+        let outcome = outcomes.get(&alert.id);
+        let category = outcome
+            .and_then(|o| o.category.clone())
+            .unwrap_or_else(|| "Uncategorized".to_string());
+
+        report.overall.add(alert, outcome);
+        report.by_detector.entry(alert.detector.to_string()).or_default().add(alert, outcome);
+        report.by_category.entry(category).or_default().add(alert, outcome);
+        report.by_level.entry(alert.alert_level.to_string()).or_default().add(alert, outcome);
+    }
+
+    report.overall.finish();
+    for group in [&mut report.by_detector, &mut report.by_category, &mut report.by_level] {
+        group.values_mut().for_each(AlertStats::finish);
+    }
+    report
+}
+
+/// Run the `report` command, following up on pending alerts first
+pub async fn run_report_command(json: bool, refresh: bool) -> Result<()> {
+    if refresh {
+        let mut client = ApiClient::new();
+        let summary = update_outcomes(&mut client, usize::MAX).await?;
+        if !json {
+            println!(
+                "{} Followed up {} alerts, {} newly resolved",
+                "📒".cyan(),
+                summary.checked,
+                summary.newly_resolved
+            );
+        }
+    }
+
+    let report = build_report(&load_alerts()?, &load_outcomes()?);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+fn format_pct(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:+.0}%", v * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_row(label: &str, stats: &AlertStats) {
+    let precision = stats
+        .precision
+        .map(|p| format!("{:.0}%", p * 100.0))
+        .unwrap_or_else(|| "-".to_string());
+    let moves: Vec<String> = PRICE_HORIZONS_HOURS
+        .iter()
+        .map(|h| {
+            stats
+                .avg_price_move
+                .get(h)
+                .map(|m| format!("{:+.1}", m * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        })
+        .collect();
+    println!(
+        "  {:<28} {:>6} {:>8} {:>9} {:>10} {:>12}   {}",
+        label,
+        stats.alerts,
+        stats.resolved,
+        precision,
+        format_pct(stats.avg_return),
+        format!("{}${:.0}", if stats.pnl_usd >= 0.0 { "+" } else { "-" }, stats.pnl_usd.abs()),
+        moves.join(" / ")
+    );
+}
+
+fn print_table(title: &str, rows: &BTreeMap<String, AlertStats>) {
+    println!();
+    println!("{}", title.bold());
+    print_header();
+    for (label, stats) in rows {
+        print_row(label, stats);
+    }
+}
+
+fn print_header() {
+    let horizons: Vec<String> = PRICE_HORIZONS_HOURS.iter().map(|h| format!("{}h", h)).collect();
+    println!(
+        "  {:<28} {:>6} {:>8} {:>9} {:>10} {:>12}   move pts {}",
+        "",
+        "alerts",
+        "resolved",
+        "precision",
+        "avg ret",
+        "PnL",
+        horizons.join(" / ")
+    );
+}
+
+pub fn print_report(report: &Report) {
+    let divider = "═".repeat(65);
+    println!();
+    println!("{}", divider.bright_white());
+    println!("📊 {}", "ALERT PRECISION REPORT".bold());
+    println!("{}", divider.bright_white());
+    print_header();
+    print_row("All alerts", &report.overall);
+    print_table("🧭 By detector", &report.by_detector);
+    print_table("🏷️  By category", &report.by_category);
+    print_table("🚦 By level", &report.by_level);
+    println!("{}", divider.bright_white());
+    println!();
+}
@@ -0,0 +1,110 @@
+//! Suspicion scoring
+//!
+//! Each feature contributes points to a 0-100 score. The breakdown is kept so
+//! alerts can show why a trade scored the way it did.
+
+use serde::{Deserialize, Serialize};
+
+use crate::chain::FundingSourceKind;
+use crate::types::TrackRecord;
+
+/// One feature's contribution to the score
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScoreComponent {
+    pub name: String,
+    pub points: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SuspicionScore {
+    pub components: Vec<ScoreComponent>,
+}
+
+impl SuspicionScore {
+    /// Add a feature's points (zero-point features are skipped)
+    pub fn add(&mut self, name: &str, points: f64) {
+        if points > 0.0 {
+            self.components.push(ScoreComponent {
+                name: name.to_string(),
+                points,
+            });
+        }
+    }
+
+    /// Total score, capped at 100
+    pub fn total(&self) -> f64 {
+        self.components.iter().map(|c| c.points).sum::<f64>().min(100.0)
+    }
+
+    /// Compact breakdown, e.g. "fresh 30 + size 20 + odds 12"
+    pub fn breakdown(&self) -> String {
+        self.components
+            .iter()
+            .map(|c| format!("{} {:.0}", c.name, c.points))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
+
+// ============================================================================
+// FEATURES
+// ============================================================================
+
+/// Fresh wallet: the core insider signal
+pub fn freshness_points(is_fresh: bool) -> f64 {
+    if is_fresh {
+        30.0
+    } else {
+        0.0
+    }
+}
+
+/// Position size relative to the minimum trade size (up to 20 at 2x)
+pub fn size_points(value_usd: f64, min_size_usd: f64) -> f64 {
+    if min_size_usd <= 0.0 {
+        return 0.0;
+    }
+    (10.0 * value_usd / min_size_usd).min(20.0)
+}
+
+/// How far below the contrarian threshold the price is (up to 20)
+pub fn contrarian_points(price: f64, max_price: f64) -> f64 {
+    if max_price <= 0.0 || price >= max_price {
+        return 0.0;
+    }
+    20.0 * (1.0 - price / max_price)
+}
+
+/// Closeness to scheduled resolution inside the pre-resolution window (up to 30)
+pub fn timing_points(hours_to_resolution: f64, window_hours: f64) -> f64 {
+    if window_hours <= 0.0 || hours_to_resolution > window_hours {
+        return 0.0;
+    }
+    30.0 * (1.0 - hours_to_resolution.max(0.0) / window_hours)
+}
+
+/// Where the wallet's money came from, and how recently (up to 25)
+pub fn funding_points(kind: &FundingSourceKind, lead_hours: f64) -> f64 {
+    let base: f64 = match kind {
+        FundingSourceKind::FlaggedWallet => 25.0,
+        FundingSourceKind::Mixer => 20.0,
+        FundingSourceKind::Exchange => 5.0,
+        FundingSourceKind::Unknown => 0.0,
+    };
+    // Money that arrived just before the trade is a burner pattern
+    let recency = if (0.0..=24.0).contains(&lead_hours) { 10.0 } else { 0.0 };
+    (base + recency).min(25.0)
+}
+
+/// Linked to previously flagged wallets (up to 25)
+pub fn linkage_points(linked_flagged: usize) -> f64 {
+    (15.0 + 5.0 * linked_flagged as f64).min(25.0)
+}
+
+/// Long-shot hit rate on resolved markets, once there are enough bets (up to 25)
+pub fn record_points(record: &TrackRecord, min_longshots: usize) -> f64 {
+    match record.longshot_hit_rate() {
+        Some(rate) if record.longshot_bets >= min_longshots => 25.0 * rate,
+        _ => 0.0,
+    }
+}
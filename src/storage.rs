@@ -0,0 +1,107 @@
+//! Local JSON persistence under DATA_DIR
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::config::data_dir;
+
+/// Path of a file inside the data directory
+pub fn data_path(name: &str) -> PathBuf {
+    PathBuf::from(data_dir()).join(name)
+}
+
+/// Last modification time of a data file (None if it doesn't exist yet)
+pub fn modified_at(name: &str) -> Option<SystemTime> {
+    fs::metadata(data_path(name)).and_then(|m| m.modified()).ok()
+}
+
+/// Load a JSON file from the data directory (None if it doesn't exist yet)
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Result<Option<T>> {
+    let path = data_path(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let value = serde_json::from_str(&raw).with_context(|| format!("Invalid JSON in {}", path.display()))?;
+    Ok(Some(value))
+}
+
+/// Write a JSON file to the data directory, atomically via a temp file
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let path = data_path(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("tmp");
+    let raw = serde_json::to_string_pretty(value)?;
+    fs::write(&tmp, raw).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Append one JSON record as a line to a file in the data directory
+pub fn append_jsonl<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    append_jsonl_all(name, std::slice::from_ref(value))
+}
+
+/// Append several JSON records, one per line
+pub fn append_jsonl_all<T: Serialize>(name: &str, values: &[T]) -> Result<()> {
+    let path = data_path(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    for value in values {
+        writeln!(file, "{}", serde_json::to_string(value)?)?;
+    }
+    Ok(())
+}
+
+/// Replace a JSON-lines file with the given records, atomically via a temp file
+pub fn save_jsonl<T: Serialize>(name: &str, values: &[T]) -> Result<()> {
+    let path = data_path(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut raw = String::new();
+    for value in values {
+        raw.push_str(&serde_json::to_string(value)?);
+        raw.push('\n');
+    }
+    fs::write(&tmp, raw).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Load every record of a JSON-lines file (empty if it doesn't exist).
+/// Malformed lines are skipped with a warning rather than failing the load.
+pub fn load_jsonl<T: DeserializeOwned>(name: &str) -> Result<Vec<T>> {
+    let path = data_path(name);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let file = fs::File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut records = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => eprintln!("⚠️  Skipping bad line {} in {}: {}", i + 1, path.display(), e),
+        }
+    }
+    Ok(records)
+}
@@ -2,6 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::chain::WalletFunding;
+use crate::config::Thresholds;
+use crate::scoring::SuspicionScore;
+
 // ============================================================================
 // TRADE TYPES (from Data API /trades endpoint)
 // ============================================================================
@@ -92,11 +96,39 @@ impl Trade {
         self.side.to_uppercase() == "BUY"
     }
     
+    /// Check if this is a taker SELL
+    pub fn is_taker_sell(&self) -> bool {
+        self.side.to_uppercase() == "SELL"
+    }
+
+    /// Price of the position actually taken: selling an outcome at `p`
+    /// is a bet against it, equivalent to buying the other side at `1 - p`
+    pub fn effective_price(&self) -> f64 {
+        if self.is_taker_sell() {
+            1.0 - self.price
+        } else {
+            self.price
+        }
+    }
+    
     /// Get a unique ID for deduplication
     pub fn unique_id(&self) -> String {
         format!("{}-{}-{}", self.proxy_wallet, self.timestamp, self.size)
     }
     
+    /// Market identifier (condition ID, falling back to slug)
+    pub fn market_key(&self) -> Option<String> {
+        self.condition_id.clone().or_else(|| self.slug.clone())
+    }
+
+    /// Outcome label (falling back to outcome index)
+    pub fn outcome_key(&self) -> String {
+        self.outcome
+            .clone()
+            .or_else(|| self.outcome_index.map(|i| i.to_string()))
+            .unwrap_or_default()
+    }
+
     /// Get the Polymarket URL for this market
     pub fn market_url(&self) -> String {
         if let Some(slug) = &self.event_slug {
@@ -107,6 +139,13 @@ impl Trade {
             "https://polymarket.com".to_string()
         }
     }
+
+    /// Block explorer link for the trade's transaction
+    pub fn tx_url(&self) -> Option<String> {
+        self.transaction_hash
+            .as_ref()
+            .map(|hash| format!("https://polygonscan.com/tx/{}", hash))
+    }
 }
 
 // ============================================================================
@@ -136,27 +175,245 @@ pub struct UserActivity {
     pub slug: Option<String>,
     #[serde(default)]
     pub outcome: Option<String>,
+    #[serde(default)]
+    pub outcome_index: Option<i32>,
+    /// USD value of the activity
+    #[serde(default)]
+    pub usdc_size: Option<f64>,
     #[serde(default, rename = "type")]
     pub activity_type: Option<String>,
 }
 
+// ============================================================================
+// MARKET TYPES (from Gamma API /markets endpoint)
+// ============================================================================
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Market {
+    #[serde(default)]
+    pub condition_id: Option<String>,
+    #[serde(default)]
+    pub question: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// Scheduled end / resolution date (ISO 8601)
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub closed: Option<bool>,
+    /// JSON-encoded list of outcome labels, e.g. "[\"Yes\", \"No\"]"
+    #[serde(default)]
+    pub outcomes: Option<String>,
+    /// JSON-encoded list of outcome prices, e.g. "[\"0.7\", \"0.3\"]"
+    #[serde(default)]
+    pub outcome_prices: Option<String>,
+    /// Part of a negRisk (mutually exclusive multi-outcome) event
+    #[serde(default)]
+    pub neg_risk: Option<bool>,
+    /// Candidate/option name within a grouped event
+    #[serde(default)]
+    pub group_item_title: Option<String>,
+    /// Topic, e.g. "Politics" (not set on every market)
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+impl Market {
+    /// Outcome labels, in outcome index order
+    pub fn outcome_labels(&self) -> Vec<String> {
+        self.outcomes
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok())
+            .unwrap_or_default()
+    }
+
+    /// Current outcome prices, in outcome index order
+    pub fn outcome_price_list(&self) -> Vec<f64> {
+        self.outcome_prices
+            .as_deref()
+            .and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok())
+            .map(|prices| prices.iter().filter_map(|p| p.parse().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Index of the winning outcome once the market has resolved
+    pub fn winning_outcome_index(&self) -> Option<usize> {
+        if self.closed != Some(true) {
+            return None;
+        }
+        self.outcome_price_list().iter().position(|p| *p >= 0.99)
+    }
+
+    /// Display name of this market within its event
+    pub fn display_name(&self) -> &str {
+        self.group_item_title
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .or(self.question.as_deref())
+            .unwrap_or("Unknown Market")
+    }
+
+    /// Scheduled end date as a unix timestamp
+    pub fn end_timestamp(&self) -> Option<i64> {
+        let raw = self.end_date.as_deref()?;
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+            return Some(dt.timestamp());
+        }
+        // Some markets only carry a date
+        chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc().timestamp())
+    }
+}
+
+/// Event grouping sibling markets (from Gamma API /events endpoint)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    #[serde(default)]
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub neg_risk: Option<bool>,
+    #[serde(default)]
+    pub markets: Vec<Market>,
+}
+
+/// One point of an outcome token's price history (CLOB /prices-history)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PricePoint {
+    pub t: i64,
+    pub p: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceHistory {
+    #[serde(default)]
+    pub history: Vec<PricePoint>,
+}
+
+// ============================================================================
+// POSITION TYPES (from Data API /positions endpoint)
+// ============================================================================
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    #[serde(default)]
+    pub condition_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub size: f64,
+    #[serde(default)]
+    pub avg_price: f64,
+    #[serde(default)]
+    pub cur_price: f64,
+    #[serde(default)]
+    pub current_value: f64,
+    #[serde(default)]
+    pub cash_pnl: f64,
+}
+
 // ============================================================================
 // USER STATS
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct UserStats {
     pub address: String,
     pub unique_markets: usize,
     pub total_trades: usize,
     pub first_activity_timestamp: Option<i64>,
+    /// First USDC funding transfer, when a chain provider is configured
+    pub funding: Option<WalletFunding>,
+    /// Profitability on past markets, computed on demand for low-volume wallets
+    pub record: Option<TrackRecord>,
+}
+
+impl UserStats {
+    /// Check the fresh-wallet criteria at `now_ts`, returning an age description if it passes
+    pub fn fresh_age(&self, thresholds: &Thresholds, now_ts: i64) -> Option<String> {
+        // CRITERIA 1: Fresh Wallet (Few Markets)
+        if self.unique_markets > thresholds.max_unique_markets {
+            return None;
+        }
+
+        // CRITERIA 2: Fresh Wallet (Time)
+        // If we have activity data, ensure the wallet is young (created/first active recently)
+        match self.first_activity_timestamp {
+            Some(first_act) => {
+                let age_hours = (now_ts - first_act) / 3600;
+
+                if age_hours > thresholds.max_wallet_age_hours as i64 {
+                    return None; // Wallet is too old (> 24h)
+                }
+                Some(format!("{}h old", age_hours))
+            }
+            None => Some("New".to_string()),
+        }
+    }
+}
+
+/// A wallet's results on the markets it traded
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TrackRecord {
+    /// Positions in markets that have resolved
+    pub resolved_positions: usize,
+    /// Resolved positions on the winning outcome
+    pub wins: usize,
+    /// Realized PnL: sells, plus resolved payouts, minus cost
+    pub realized_pnl: f64,
+    pub avg_entry_winning: Option<f64>,
+    pub avg_entry_losing: Option<f64>,
+    /// Resolved positions entered below the contrarian threshold
+    pub longshot_bets: usize,
+    pub longshot_wins: usize,
+}
+
+impl TrackRecord {
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.resolved_positions > 0).then(|| self.wins as f64 / self.resolved_positions as f64)
+    }
+
+    pub fn longshot_hit_rate(&self) -> Option<f64> {
+        (self.longshot_bets > 0).then(|| self.longshot_wins as f64 / self.longshot_bets as f64)
+    }
+
+    /// e.g. "4/5 won (80%), PnL +$12345, long shots 3/3, entry 14% wins vs 52% losses"
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}/{} won ({:.0}%), PnL {}${:.0}, long shots {}/{}",
+            self.wins,
+            self.resolved_positions,
+            self.win_rate().unwrap_or(0.0) * 100.0,
+            if self.realized_pnl >= 0.0 { "+" } else { "-" },
+            self.realized_pnl.abs(),
+            self.longshot_wins,
+            self.longshot_bets
+        );
+        if let (Some(win), Some(loss)) = (self.avg_entry_winning, self.avg_entry_losing) {
+            summary.push_str(&format!(
+                ", entry {:.0}% wins vs {:.0}% losses",
+                win * 100.0,
+                loss * 100.0
+            ));
+        }
+        summary
+    }
 }
 
 // ============================================================================
 // SUSPECT / ALERT TYPES
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum AlertLevel {
     High,
     Medium,
@@ -173,10 +430,140 @@ impl std::fmt::Display for AlertLevel {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Which detection rule produced an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Detector {
+    /// Single large contrarian taker BUY from a fresh wallet
+    FreshWallet,
+    /// Many small fills adding up to a large position
+    SplitOrder,
+    /// Several fresh wallets buying the same outcome together
+    Cluster,
+    /// Taker-buy volume far above the market's baseline
+    VolumeSpike,
+    /// Sustained price move away from the market's baseline
+    PriceDrift,
+    /// Fresh wallet aggressively selling the favourite
+    ContrarianSell,
+    /// Previously flagged wallet selling out of a position
+    InformedExit,
+    /// Established but low-volume wallet with an implausible long-shot record,
+    /// buying the long shot or selling the favourite
+    LongShotRecord,
+    /// Any trade by a wallet on the watchlist
+    WatchedWallet,
+    /// Fresh wallet trading in a watched market set to alert on any fresh activity
+    WatchedMarket,
+}
+
+impl Detector {
+    /// Stable name, the same as the serialized one (e.g. "fresh_wallet");
+    /// part of persisted alert IDs, so never rename one
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Detector::FreshWallet => "fresh_wallet",
+            Detector::SplitOrder => "split_order",
+            Detector::Cluster => "cluster",
+            Detector::VolumeSpike => "volume_spike",
+            Detector::PriceDrift => "price_drift",
+            Detector::ContrarianSell => "contrarian_sell",
+            Detector::InformedExit => "informed_exit",
+            Detector::LongShotRecord => "long_shot_record",
+            Detector::WatchedWallet => "watched_wallet",
+            Detector::WatchedMarket => "watched_market",
+        }
+    }
+
+    /// Alerts about a specific wallet's behaviour (not market-wide anomalies)
+    pub fn is_wallet_level(&self) -> bool {
+        !matches!(self, Detector::VolumeSpike | Detector::PriceDrift)
+    }
+}
+
+impl std::fmt::Display for Detector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Detector::FreshWallet => write!(f, "Fresh Wallet"),
+            Detector::SplitOrder => write!(f, "Split Order"),
+            Detector::Cluster => write!(f, "Wallet Cluster"),
+            Detector::VolumeSpike => write!(f, "Volume Spike"),
+            Detector::PriceDrift => write!(f, "Price Drift"),
+            Detector::ContrarianSell => write!(f, "Contrarian Sell"),
+            Detector::InformedExit => write!(f, "Informed Exit"),
+            Detector::LongShotRecord => write!(f, "Long-shot Record"),
+            Detector::WatchedWallet => write!(f, "Watched Wallet Activity"),
+            Detector::WatchedMarket => write!(f, "Watched Market Fresh Wallet"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SuspectTrade {
     pub trade: Trade,
     pub user_stats: UserStats,
     pub reason: String,
     pub alert_level: AlertLevel,
+    pub detector: Detector,
+    /// Constituent fills of an aggregated position (empty for single trades)
+    pub fills: Vec<Trade>,
+    /// Stats of every wallet in a cluster, in order of first appearance
+    /// (empty for single-wallet alerts)
+    #[serde(default)]
+    pub members: Vec<UserStats>,
+    /// Market metadata, when the Gamma API had it
+    pub market: Option<Market>,
+    /// What the trader is actually betting on, in event context
+    pub bet: Option<String>,
+    pub score: SuspicionScore,
+}
+
+impl SuspectTrade {
+    /// Stable ID of the alert: the trigger trade plus the detector name
+    pub fn alert_id(&self) -> String {
+        format!("{}-{}", self.trade.unique_id(), self.detector.as_str())
+    }
+
+    /// Stats of each wallet behind the alert
+    pub fn wallet_stats(&self) -> Vec<&UserStats> {
+        if self.members.is_empty() {
+            vec![&self.user_stats]
+        } else {
+            self.members.iter().collect()
+        }
+    }
+
+    /// Total USD value of the position (sum of fills when aggregated)
+    pub fn position_value_usd(&self) -> f64 {
+        if self.fills.is_empty() {
+            self.trade.value_usd()
+        } else {
+            self.fills.iter().map(|f| f.value_usd()).sum()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detector_names_match_serialized_names() {
+        let all = [
+            Detector::FreshWallet,
+            Detector::SplitOrder,
+            Detector::Cluster,
+            Detector::VolumeSpike,
+            Detector::PriceDrift,
+            Detector::ContrarianSell,
+            Detector::InformedExit,
+            Detector::LongShotRecord,
+            Detector::WatchedWallet,
+            Detector::WatchedMarket,
+        ];
+        for detector in all {
+            let serialized = serde_json::to_value(detector).unwrap();
+            assert_eq!(serialized.as_str(), Some(detector.as_str()));
+        }
+    }
 }
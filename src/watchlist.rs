@@ -0,0 +1,170 @@
+//! Wallet watchlist
+//!
+//! Once a wallet is flagged we want to see everything it does next, not just
+//! the trades that happen to pass the filter chain again. Watched wallets come
+//! from WATCHLIST_WALLETS and from `DATA_DIR/watchlist.json`, which is managed
+//! with the `watch` command and by auto-adding wallets behind HIGH alerts.
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::mask_address;
+use crate::config::watchlist_wallets;
+use crate::storage::{load_json, modified_at, save_json};
+
+const WATCHLIST_FILE: &str = "watchlist.json";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WatchEntry {
+    pub added_at: i64,
+    /// Why the wallet is watched, e.g. "manual" or "auto: Fresh Wallet HIGH alert"
+    pub note: String,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct Watchlist {
+    wallets: BTreeMap<String, WatchEntry>,
+    /// From WATCHLIST_WALLETS, never persisted
+    #[serde(skip)]
+    configured: Vec<String>,
+    #[serde(skip)]
+    dirty: bool,
+    /// Modification time of the file as last read or written
+    #[serde(skip)]
+    file_mtime: Option<SystemTime>,
+}
+
+impl Watchlist {
+    /// Load the persisted watchlist plus the configured wallets
+    pub fn load() -> Self {
+        let mut watchlist = match load_json::<Watchlist>(WATCHLIST_FILE) {
+            Ok(Some(watchlist)) => watchlist,
+            Ok(None) => Self::default(),
+            Err(e) => {
+                eprintln!("⚠️  Watchlist not loaded, starting empty: {:#}", e);
+                Self::default()
+            }
+        };
+        watchlist.configured = watchlist_wallets();
+        watchlist.file_mtime = modified_at(WATCHLIST_FILE);
+        watchlist
+    }
+
+    /// Re-read the file if it changed since it was last read or written
+    /// (e.g. by the `watch` command). Unsaved changes are kept as they are.
+    pub fn reload_if_changed(&mut self) {
+        if !self.dirty && modified_at(WATCHLIST_FILE) != self.file_mtime {
+            *self = Self::load();
+        }
+    }
+
+    /// Persist if anything changed since the last save
+    pub fn save_if_dirty(&mut self) -> Result<()> {
+        if self.dirty {
+            save_json(WATCHLIST_FILE, self)?;
+            self.dirty = false;
+            self.file_mtime = modified_at(WATCHLIST_FILE);
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, wallet: &str) -> bool {
+        let wallet = wallet.to_lowercase();
+        self.wallets.contains_key(&wallet) || self.configured.contains(&wallet)
+    }
+
+    /// Number of distinct watched wallets
+    pub fn count(&self) -> usize {
+        self.configured
+            .iter()
+            .filter(|w| !self.wallets.contains_key(*w))
+            .count()
+            + self.wallets.len()
+    }
+
+    /// Add a wallet. Returns false if it was already watched.
+    pub fn add(&mut self, wallet: &str, note: &str) -> bool {
+        if self.contains(wallet) {
+            return false;
+        }
+        self.wallets.insert(
+            wallet.to_lowercase(),
+            WatchEntry {
+                added_at: chrono::Utc::now().timestamp(),
+                note: note.to_string(),
+            },
+        );
+        self.dirty = true;
+        true
+    }
+
+    /// Remove a persisted wallet. Returns false if it wasn't on the list.
+    pub fn remove(&mut self, wallet: &str) -> bool {
+        let removed = self.wallets.remove(&wallet.to_lowercase()).is_some();
+        self.dirty |= removed;
+        removed
+    }
+}
+
+// ============================================================================
+// CLI
+// ============================================================================
+
+pub enum WatchAction {
+    Add(String),
+    Remove(String),
+    List,
+}
+
+/// Run the `watch` command
+pub fn run_watch_command(action: WatchAction) -> Result<()> {
+    let mut watchlist = Watchlist::load();
+
+    match action {
+        WatchAction::Add(wallet) => {
+            if watchlist.add(&wallet, "manual") {
+                println!("{} Watching {}", "👀".cyan(), wallet);
+            } else {
+                println!("{} {} is already watched", "ℹ️".cyan(), wallet);
+            }
+        }
+        WatchAction::Remove(wallet) => {
+            if watchlist.remove(&wallet) {
+                println!("{} No longer watching {}", "✅".green(), wallet);
+            } else if watchlist.contains(&wallet) {
+                println!("{} {} is set in WATCHLIST_WALLETS; remove it there", "⚠️".yellow(), wallet);
+            } else {
+                println!("{} {} is not watched", "ℹ️".cyan(), wallet);
+            }
+        }
+        WatchAction::List => {
+            println!("{} ({})", "👀 Watched wallets".bold(), watchlist.count());
+            for wallet in &watchlist.configured {
+                if !watchlist.wallets.contains_key(wallet) {
+                    println!("  {}  (WATCHLIST_WALLETS)", wallet);
+                }
+            }
+            for (wallet, entry) in &watchlist.wallets {
+                let added = chrono::DateTime::from_timestamp(entry.added_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+                    .unwrap_or_default();
+                println!("  {}  {}  since {}", wallet, entry.note, added);
+            }
+        }
+    }
+
+    watchlist.save_if_dirty()
+}
+
+/// Auto-add a wallet behind a HIGH alert, announcing it on the console.
+/// Re-reads the file first, so saving doesn't undo a `watch` edit.
+pub fn auto_watch(watchlist: &mut Watchlist, wallet: &str, note: &str) {
+    watchlist.reload_if_changed();
+    if watchlist.add(wallet, note) {
+        println!("{} Now watching {} ({})", "👀".cyan(), mask_address(wallet), note);
+    }
+}